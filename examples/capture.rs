@@ -0,0 +1,57 @@
+//! Render the first stage with the SDL software renderer and capture screenshots, no window or
+//! GPU needed
+//!
+//! Usage: `SDL_AUDIODRIVER=dummy cargo run --example capture [ticks] [directory]`
+
+use std::{path::PathBuf, time::Instant};
+
+use anyhow::{Context, Result};
+use deimosreborn::{
+    errors::SdlError,
+    resource::{display::Display, player_input::PlayerInput, screenshot::Resolution},
+    GameBuilder,
+};
+use sdl2::{pixels::PixelFormatEnum, surface::Surface};
+
+const DEFAULT_TICKS: u64 = 120;
+const DEFAULT_DIRECTORY: &str = "screenshots";
+
+fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+    let ticks = match args.next() {
+        Some(arg) => arg.parse().context("invalid number of ticks")?,
+        None => DEFAULT_TICKS,
+    };
+    let directory = PathBuf::from(args.next().as_deref().unwrap_or(DEFAULT_DIRECTORY));
+
+    // Sound samples are loaded along with the textures
+    let _sdl_context = sdl2::init().map_err(SdlError::InitError)?;
+    sdl2::mixer::open_audio(44100, sdl2_sys::mixer::MIX_DEFAULT_FORMAT as u16, 2, 1024)
+        .map_err(SdlError::InitError)
+        .context("Failed to open audio driver")?;
+
+    // A surface the size of the default window stands in for the window
+    let display = Display::default();
+    let (width, height) = display.window_size();
+    let canvas = Surface::new(width, height, PixelFormatEnum::RGB888)
+        .map_err(SdlError::InitError)?
+        .into_canvas()
+        .map_err(SdlError::InitError)?;
+    let texture_creator = canvas.texture_creator();
+    let mut game = GameBuilder::new()
+        .with_display(display)
+        .build(canvas, &texture_creator)?;
+
+    for _ in 0..ticks {
+        game.tick(PlayerInput::default(), Instant::now());
+    }
+
+    let native = directory.join(format!("tick_{}_native.png", ticks));
+    let window = directory.join(format!("tick_{}_window.png", ticks));
+    game.screenshot(&native, Resolution::Native)?;
+    game.screenshot(&window, Resolution::Window)?;
+
+    println!("Saved {} and {}", native.display(), window.display());
+
+    Ok(())
+}
@@ -0,0 +1,137 @@
+//! Headless collision benchmark with thousands of bullets
+//!
+//! Usage: `cargo run --release --example collision_benchmark [bullets] [ticks]`
+
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use deimosreborn::{
+    component::{
+        bullet_physics::BulletPhysicsComponent,
+        hitbox::{CollisionLayers, HitboxComponent, HitboxShape},
+        position::PositionComponent,
+    },
+    headless::Simulation,
+    resource::{collision::Collisions, player_input::PlayerInput, spatial_hash::SpatialHash},
+    GameBuilder, GAME_HEIGHT, GAME_WIDTH,
+};
+use specs::{Builder, WorldExt};
+
+const DEFAULT_BULLETS: usize = 5000;
+const DEFAULT_TICKS: u64 = 600;
+
+/// Small deterministic pseudo random generator, good enough to scatter bullets
+struct Lcg(u64);
+
+impl Lcg {
+    fn next_f32(&mut self) -> f32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1);
+    let bullets = match args.next() {
+        Some(arg) => arg.parse().context("invalid number of bullets")?,
+        None => DEFAULT_BULLETS,
+    };
+    let ticks = match args.next() {
+        Some(arg) => arg.parse().context("invalid number of ticks")?,
+        None => DEFAULT_TICKS,
+    };
+
+    let mut simulation = Simulation::from_game(
+        GameBuilder::new()
+            .without_player()
+            .without_level()
+            .build_headless()?,
+    );
+
+    // Player bullets and enemy bullets that collide with each other, drifting slowly
+    // so most of them stay on the playfield
+    let mut rng = Lcg(0x5eed);
+    let world = simulation.game_mut().world_mut();
+    for i in 0..bullets {
+        let (layer, mask) = if i % 2 == 0 {
+            (
+                CollisionLayers::PLAYER_BULLETS,
+                CollisionLayers::ENEMY_BULLETS,
+            )
+        } else {
+            (
+                CollisionLayers::ENEMY_BULLETS,
+                CollisionLayers::PLAYER_BULLETS,
+            )
+        };
+
+        world
+            .create_entity()
+            .with(PositionComponent::new(
+                rng.next_f32() * GAME_WIDTH as f32,
+                rng.next_f32() * GAME_HEIGHT as f32,
+            ))
+            .with(BulletPhysicsComponent {
+                vx: (rng.next_f32() - 0.5) * 60.0,
+                vy: (rng.next_f32() - 0.5) * 60.0,
+            })
+            .with(HitboxComponent::new(
+                HitboxShape::Circle { radius: 3.0 },
+                layer,
+                mask,
+            ))
+            .build();
+    }
+    world.maintain();
+
+    let mut collisions = 0;
+    let start = Instant::now();
+    for _ in 0..ticks {
+        simulation.step(PlayerInput::default())?;
+        collisions += simulation
+            .world()
+            .read_resource::<Collisions>()
+            .events()
+            .len();
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "{} bullets, {} ticks: {:.3} ms / tick, {} collision events",
+        bullets,
+        ticks,
+        elapsed.as_secs_f64() * 1000.0 / ticks as f64,
+        collisions
+    );
+
+    // Queries as used by homing and targeting
+    let spatial_hash = simulation.world().read_resource::<SpatialHash>();
+    let queries = 10_000;
+    let start = Instant::now();
+    let mut found = 0;
+    for _ in 0..queries {
+        let x = rng.next_f32() * GAME_WIDTH as f32;
+        let y = rng.next_f32() * GAME_HEIGHT as f32;
+        found += spatial_hash
+            .query_region(x - 16.0, y - 16.0, x + 16.0, y + 16.0)
+            .len();
+        found += spatial_hash
+            .nearest(x, y, |entry| {
+                entry.layer.intersects(CollisionLayers::ENEMY_BULLETS)
+            })
+            .map_or(0, |_| 1);
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "{} region + nearest queries: {:.3} us / query pair, {} results",
+        queries,
+        elapsed.as_secs_f64() * 1_000_000.0 / queries as f64,
+        found
+    );
+
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::{bail, Result};
+
+/// What happens when a clip reaches its last frame
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PlaybackMode {
+    /// Start over with the first frame
+    Loop,
+    /// Play backwards to the first frame, then forwards again
+    PingPong,
+    /// Stay on the last frame
+    Once,
+}
+
+/// Sequence of sprite frames, each shown for its own duration
+#[derive(Debug, Clone)]
+pub struct AnimationClip {
+    pub frames: Vec<usize>,
+    /// Time each frame is shown in s, same length as `frames`
+    pub durations: Vec<f32>,
+    pub mode: PlaybackMode,
+}
+
+impl AnimationClip {
+    /// Clip showing every frame for `frame_duration` seconds
+    pub fn new<I>(frames: I, frame_duration: f32, mode: PlaybackMode) -> Self
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        let frames: Vec<_> = frames.into_iter().collect();
+        Self {
+            durations: vec![frame_duration; frames.len()],
+            frames,
+            mode,
+        }
+    }
+
+    /// Replace the durations of all frames, in s
+    pub fn with_frame_durations(mut self, durations: Vec<f32>) -> Self {
+        self.durations = durations;
+        self
+    }
+
+    /// Check the clip against the number of frames of its sprite
+    pub fn validate(&self, number_of_frames: usize) -> Result<()> {
+        if self.frames.is_empty() {
+            bail!("clip has no frames");
+        }
+        if self.durations.len() != self.frames.len() {
+            bail!(
+                "clip has {} frames, but {} durations",
+                self.frames.len(),
+                self.durations.len()
+            );
+        }
+        if let Some(frame) = self.frames.iter().find(|&&frame| frame >= number_of_frames) {
+            bail!(
+                "frame {} is out of range, the sprite has {} frames",
+                frame,
+                number_of_frames
+            );
+        }
+        if self.durations.iter().any(|&duration| duration <= 0.0) {
+            bail!("frame durations must be positive");
+        }
+
+        Ok(())
+    }
+}
+
+/// Animation clips of a sprite, by name
+#[derive(Debug, Clone, Default)]
+pub struct AnimationClips {
+    clips: HashMap<String, Arc<AnimationClip>>,
+}
+
+impl AnimationClips {
+    pub fn get(&self, name: &str) -> Option<Arc<AnimationClip>> {
+        self.clips.get(name).cloned()
+    }
+
+    pub fn insert(&mut self, name: &str, clip: AnimationClip) {
+        self.clips.insert(name.to_string(), Arc::new(clip));
+    }
+}
+
+/// Playback position within a clip
+#[derive(Debug, Clone)]
+pub struct Playback {
+    /// Index into the frames of the clip
+    index: usize,
+    /// Time the current frame has been shown in s
+    elapsed: f32,
+    /// Direction of ping-pong clips
+    forward: bool,
+    finished: bool,
+}
+
+impl Default for Playback {
+    fn default() -> Self {
+        Self {
+            index: 0,
+            elapsed: 0.0,
+            forward: true,
+            finished: false,
+        }
+    }
+}
+
+impl Playback {
+    pub fn frame(&self, clip: &AnimationClip) -> usize {
+        clip.frames[self.index]
+    }
+
+    /// A clip played once has reached its last frame
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Advance by `dt` seconds, skipping frames if `dt` is longer than a frame
+    pub fn advance(&mut self, clip: &AnimationClip, dt: f32) {
+        let last = clip.frames.len() - 1;
+        if self.finished {
+            return;
+        }
+
+        self.elapsed += dt;
+        while self.elapsed >= clip.durations[self.index] {
+            self.elapsed -= clip.durations[self.index];
+
+            self.index = match clip.mode {
+                PlaybackMode::Loop if self.index == last => 0,
+                PlaybackMode::Once if self.index == last => {
+                    self.finished = true;
+                    self.elapsed = 0.0;
+                    return;
+                }
+                PlaybackMode::Loop | PlaybackMode::Once => self.index + 1,
+                PlaybackMode::PingPong if last == 0 => 0,
+                PlaybackMode::PingPong => {
+                    if (self.forward && self.index == last) || (!self.forward && self.index == 0) {
+                        self.forward = !self.forward;
+                    }
+                    if self.forward {
+                        self.index + 1
+                    } else {
+                        self.index - 1
+                    }
+                }
+            };
+        }
+    }
+}
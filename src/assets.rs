@@ -0,0 +1,527 @@
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::{Context, Result};
+use sdl2::{pixels::Color, render::TextureCreator};
+
+use crate::{
+    animation::{AnimationClip, AnimationClips, PlaybackMode},
+    errors::SdlError,
+    sound::{SoundId, SoundLibrary},
+    sprite::{Sprite, SpriteDescription, SpriteId, SpriteManager},
+};
+
+/// Looping spin animation of the ion cannon bullet
+pub const BULLET_SPIN_CLIP: &str = "spin";
+
+/// Loads sprites and sounds and hands out the ids used by the game world
+pub trait AssetLoader {
+    fn load_sprite(
+        &mut self,
+        description: SpriteDescription,
+        path_color_map: &str,
+        path_alpha_map: &str,
+    ) -> Result<SpriteId>;
+
+    /// Single frame sprite of a filled circle, for entities without artwork yet
+    fn create_placeholder_circle(&mut self, radius: u32, color: Color) -> Result<SpriteId>;
+
+    /// Tile sheet of solid colored tiles, for backgrounds without artwork yet
+    fn create_placeholder_tiles(&mut self, tile_size: u32, colors: &[Color]) -> Result<SpriteId>;
+
+    /// Font sheet of white glyphs given as rows of `#` and `.`, for text without a font yet
+    fn create_placeholder_glyphs(
+        &mut self,
+        width: u32,
+        height: u32,
+        glyphs: &[&[&str]],
+    ) -> Result<SpriteId>;
+
+    fn load_sound(&mut self, path: &str) -> Result<SoundId>;
+}
+
+/// Sprite description matching `Sprite::create_placeholder_circle`
+fn placeholder_circle_description(radius: u32) -> SpriteDescription {
+    let diam = (radius * 2 + 1) as usize;
+    SpriteDescription {
+        number_of_frames: 1,
+        border_left: 0,
+        border_up: 0,
+        frame_dimensions: (diam, diam),
+    }
+}
+
+/// Sprite description matching `Sprite::create_placeholder_tiles`
+fn placeholder_tiles_description(tile_size: u32, tiles: usize) -> SpriteDescription {
+    SpriteDescription {
+        number_of_frames: tiles,
+        border_left: 0,
+        border_up: 0,
+        frame_dimensions: (tile_size as usize, tile_size as usize),
+    }
+}
+
+/// Sprite description matching `Sprite::create_placeholder_glyphs`
+fn placeholder_glyphs_description(width: u32, height: u32, glyphs: usize) -> SpriteDescription {
+    SpriteDescription {
+        number_of_frames: glyphs,
+        border_left: 0,
+        border_up: 0,
+        frame_dimensions: (width as usize, height as usize),
+    }
+}
+
+/// Sprite registered under a name, e.g. to be referenced from level data
+#[derive(Debug, Clone)]
+pub struct SpriteEntry {
+    pub id: SpriteId,
+    pub description: SpriteDescription,
+    /// Files the sprite is loaded from, empty for generated sprites
+    pub files: Vec<String>,
+    pub clips: Arc<AnimationClips>,
+}
+
+/// Sound registered under a name, e.g. to be referenced from level data
+#[derive(Debug, Clone)]
+pub struct SoundEntry {
+    pub id: SoundId,
+    pub file: String,
+}
+
+/// All named assets
+#[derive(Default)]
+pub struct AssetCatalog {
+    sprites: HashMap<String, SpriteEntry>,
+    sounds: HashMap<String, SoundEntry>,
+}
+
+impl AssetCatalog {
+    pub fn sprite(&self, name: &str) -> Option<&SpriteEntry> {
+        self.sprites.get(name)
+    }
+
+    pub fn sound(&self, name: &str) -> Option<&SoundEntry> {
+        self.sounds.get(name)
+    }
+
+    fn load_sprite<L: AssetLoader>(
+        &mut self,
+        loader: &mut L,
+        name: &str,
+        description: SpriteDescription,
+        path_color_map: &str,
+        path_alpha_map: &str,
+    ) -> Result<SpriteId> {
+        let id = loader.load_sprite(description, path_color_map, path_alpha_map)?;
+        self.sprites.insert(
+            name.to_string(),
+            SpriteEntry {
+                id,
+                description,
+                files: vec![path_color_map.to_string(), path_alpha_map.to_string()],
+                clips: Arc::default(),
+            },
+        );
+        Ok(id)
+    }
+
+    fn create_placeholder_circle<L: AssetLoader>(
+        &mut self,
+        loader: &mut L,
+        name: &str,
+        radius: u32,
+        color: Color,
+    ) -> Result<SpriteId> {
+        let id = loader.create_placeholder_circle(radius, color)?;
+        self.sprites.insert(
+            name.to_string(),
+            SpriteEntry {
+                id,
+                description: placeholder_circle_description(radius),
+                files: Vec::new(),
+                clips: Arc::default(),
+            },
+        );
+        Ok(id)
+    }
+
+    fn create_placeholder_tiles<L: AssetLoader>(
+        &mut self,
+        loader: &mut L,
+        name: &str,
+        tile_size: u32,
+        colors: &[Color],
+    ) -> Result<SpriteId> {
+        let id = loader.create_placeholder_tiles(tile_size, colors)?;
+        self.sprites.insert(
+            name.to_string(),
+            SpriteEntry {
+                id,
+                description: placeholder_tiles_description(tile_size, colors.len()),
+                files: Vec::new(),
+                clips: Arc::default(),
+            },
+        );
+        Ok(id)
+    }
+
+    fn create_placeholder_glyphs<L: AssetLoader>(
+        &mut self,
+        loader: &mut L,
+        name: &str,
+        width: u32,
+        height: u32,
+        glyphs: &[&[&str]],
+    ) -> Result<SpriteId> {
+        let id = loader.create_placeholder_glyphs(width, height, glyphs)?;
+        self.sprites.insert(
+            name.to_string(),
+            SpriteEntry {
+                id,
+                description: placeholder_glyphs_description(width, height, glyphs.len()),
+                files: Vec::new(),
+                clips: Arc::default(),
+            },
+        );
+        Ok(id)
+    }
+
+    /// Define an animation clip of a registered sprite
+    fn add_clip(&mut self, sprite: &str, name: &str, clip: AnimationClip) -> Result<()> {
+        let entry = self
+            .sprites
+            .get_mut(sprite)
+            .with_context(|| format!("unknown sprite \"{}\"", sprite))?;
+        clip.validate(entry.description.number_of_frames)
+            .with_context(|| format!("invalid clip \"{}\" of sprite \"{}\"", name, sprite))?;
+        Arc::make_mut(&mut entry.clips).insert(name, clip);
+        Ok(())
+    }
+
+    fn load_sound<L: AssetLoader>(
+        &mut self,
+        loader: &mut L,
+        name: &str,
+        path: &str,
+    ) -> Result<SoundId> {
+        let id = loader.load_sound(path)?;
+        self.add_sound(name, id, path);
+        Ok(id)
+    }
+
+    fn add_sound(&mut self, name: &str, id: SoundId, path: &str) {
+        self.sounds.insert(
+            name.to_string(),
+            SoundEntry {
+                id,
+                file: path.to_string(),
+            },
+        );
+    }
+}
+
+/// Ids of the assets the game world refers to directly, everything else is looked up by name
+pub struct Assets {
+    pub player_sprite: SpriteId,
+    pub player_sprite_description: SpriteDescription,
+    pub bullet_sprite: SpriteId,
+    pub glow_sprite: SpriteId,
+    pub bullet_sound: SoundId,
+    pub catalog: AssetCatalog,
+}
+
+impl Assets {
+    pub fn load<L: AssetLoader>(loader: &mut L) -> Result<Self> {
+        let mut catalog = AssetCatalog::default();
+
+        let player_sprite_description = SpriteDescription {
+            number_of_frames: 7,
+            border_left: 3,
+            border_up: 3,
+            frame_dimensions: (53, 43),
+        };
+        let player_sprite = catalog.load_sprite(
+            loader,
+            "player",
+            player_sprite_description,
+            "assets/ Data/Paks/Game/im08/Player 1 Orange IC[pl1o].gif",
+            "assets/ Data/Paks/Game/im08/Player 1 Orange IA[PL1O].gif",
+        )?;
+
+        let bullet_sprite = catalog.load_sprite(
+            loader,
+            "ion_cannon_bullet",
+            SpriteDescription {
+                number_of_frames: 36,
+                border_left: 3,
+                border_up: 3,
+                frame_dimensions: (20, 20),
+            },
+            "assets/ Data/Paks/Game/im08/Ion Cannon Bullet IC[icbu].gif",
+            "assets/ Data/Paks/Game/im08/Ion Cannon Bullet IA[ICBU].gif",
+        )?;
+        catalog.add_clip(
+            "ion_cannon_bullet",
+            BULLET_SPIN_CLIP,
+            AnimationClip::new(0..36, 0.02, PlaybackMode::Loop),
+        )?;
+
+        let glow_sprite = catalog.load_sprite(
+            loader,
+            "ion_cannon_glow",
+            SpriteDescription {
+                number_of_frames: 1,
+                border_left: 66,
+                border_up: 3,
+                frame_dimensions: (70, 70),
+            },
+            "assets/ Data/Paks/Game/im08/Ion Cannon IC[ioca].gif",
+            "assets/ Data/Paks/Game/im08/Ion Cannon IA[IOCA].gif",
+        )?;
+
+        let bullet_sound = catalog.load_sound(
+            loader,
+            "ion_cannon_bullet",
+            "assets/ Data/Paks/Audio/Ion-Cannon-Bullet_icbu_.wav",
+        )?;
+
+        // TODO: replace placeholders by the enemy and explosion sprites of the original game
+        catalog.create_placeholder_circle(
+            loader,
+            "enemy_placeholder",
+            16,
+            Color::RGB(200, 40, 40),
+        )?;
+        catalog.create_placeholder_circle(
+            loader,
+            "explosion_placeholder",
+            24,
+            Color::RGB(255, 190, 60),
+        )?;
+        catalog.create_placeholder_circle(
+            loader,
+            "enemy_bullet_placeholder",
+            4,
+            Color::RGB(255, 80, 220),
+        )?;
+        // White, tinted per particle
+        catalog.create_placeholder_circle(
+            loader,
+            "particle_placeholder",
+            2,
+            Color::RGB(255, 255, 255),
+        )?;
+
+        // TODO: replace placeholders by the terrain tiles of the original game
+        catalog.create_placeholder_tiles(
+            loader,
+            "terrain_placeholder",
+            32,
+            &[
+                Color::RGB(34, 80, 40),
+                Color::RGB(46, 104, 52),
+                Color::RGB(92, 84, 64),
+                Color::RGB(40, 70, 130),
+            ],
+        )?;
+        catalog.create_placeholder_tiles(
+            loader,
+            "clouds_placeholder",
+            32,
+            &[Color::RGB(210, 210, 220), Color::RGB(235, 235, 240)],
+        )?;
+
+        // TODO: replace placeholder by the fonts of the original game
+        let glyphs: Vec<&[&str]> = PLACEHOLDER_FONT.iter().map(|(_, rows)| &rows[..]).collect();
+        catalog.create_placeholder_glyphs(loader, "placeholder_font", 5, 7, &glyphs)?;
+
+        // FIXME: placeholder, needs a proper explosion sample
+        catalog.add_sound(
+            "explosion_placeholder",
+            bullet_sound,
+            "assets/ Data/Paks/Audio/Ion-Cannon-Bullet_icbu_.wav",
+        );
+
+        Ok(Self {
+            player_sprite,
+            player_sprite_description,
+            bullet_sprite,
+            glow_sprite,
+            bullet_sound,
+            catalog,
+        })
+    }
+}
+
+/// Loads textures and sound samples through SDL
+pub struct SdlAssetLoader<'t, T> {
+    texture_creator: &'t TextureCreator<T>,
+    sprites: SpriteManager<'t>,
+    sounds: SoundLibrary,
+}
+
+impl<'t, T> SdlAssetLoader<'t, T> {
+    pub fn new(texture_creator: &'t TextureCreator<T>) -> Self {
+        Self {
+            texture_creator,
+            sprites: SpriteManager::new(),
+            sounds: SoundLibrary::new(),
+        }
+    }
+
+    pub fn into_parts(self) -> (SpriteManager<'t>, SoundLibrary) {
+        (self.sprites, self.sounds)
+    }
+}
+
+impl<'t, T> AssetLoader for SdlAssetLoader<'t, T> {
+    fn load_sprite(
+        &mut self,
+        description: SpriteDescription,
+        path_color_map: &str,
+        path_alpha_map: &str,
+    ) -> Result<SpriteId> {
+        let sprite = Sprite::from_gif(
+            description,
+            path_color_map,
+            path_alpha_map,
+            self.texture_creator,
+        )?;
+        Ok(self.sprites.insert(sprite))
+    }
+
+    fn create_placeholder_circle(&mut self, radius: u32, color: Color) -> Result<SpriteId> {
+        let sprite = Sprite::create_placeholder_circle(radius, color, self.texture_creator)?;
+        Ok(self.sprites.insert(sprite))
+    }
+
+    fn create_placeholder_tiles(&mut self, tile_size: u32, colors: &[Color]) -> Result<SpriteId> {
+        let sprite = Sprite::create_placeholder_tiles(tile_size, colors, self.texture_creator)?;
+        Ok(self.sprites.insert(sprite))
+    }
+
+    fn create_placeholder_glyphs(
+        &mut self,
+        width: u32,
+        height: u32,
+        glyphs: &[&[&str]],
+    ) -> Result<SpriteId> {
+        let sprite =
+            Sprite::create_placeholder_glyphs(width, height, glyphs, self.texture_creator)?;
+        Ok(self.sprites.insert(sprite))
+    }
+
+    fn load_sound(&mut self, path: &str) -> Result<SoundId> {
+        let sound = sdl2::mixer::Chunk::from_file(path).map_err(SdlError::SoundLoadError)?;
+        Ok(self.sounds.insert(sound))
+    }
+}
+
+/// Hands out asset ids without touching the file system or SDL
+#[derive(Default)]
+pub struct HeadlessAssetLoader {
+    sprites: SpriteManager<'static>,
+    sounds: SoundLibrary,
+}
+
+impl HeadlessAssetLoader {
+    pub fn new() -> Self {
+        Self {
+            sprites: SpriteManager::new(),
+            sounds: SoundLibrary::new(),
+        }
+    }
+
+    pub fn into_parts(self) -> (SpriteManager<'static>, SoundLibrary) {
+        (self.sprites, self.sounds)
+    }
+}
+
+impl AssetLoader for HeadlessAssetLoader {
+    fn load_sprite(
+        &mut self,
+        description: SpriteDescription,
+        _path_color_map: &str,
+        _path_alpha_map: &str,
+    ) -> Result<SpriteId> {
+        Ok(self.sprites.insert(Sprite::without_texture(description)))
+    }
+
+    fn create_placeholder_circle(&mut self, radius: u32, _color: Color) -> Result<SpriteId> {
+        let description = placeholder_circle_description(radius);
+        Ok(self.sprites.insert(Sprite::without_texture(description)))
+    }
+
+    fn create_placeholder_tiles(&mut self, tile_size: u32, colors: &[Color]) -> Result<SpriteId> {
+        let description = placeholder_tiles_description(tile_size, colors.len());
+        Ok(self.sprites.insert(Sprite::without_texture(description)))
+    }
+
+    fn create_placeholder_glyphs(
+        &mut self,
+        width: u32,
+        height: u32,
+        glyphs: &[&[&str]],
+    ) -> Result<SpriteId> {
+        let description = placeholder_glyphs_description(width, height, glyphs.len());
+        Ok(self.sprites.insert(Sprite::without_texture(description)))
+    }
+
+    fn load_sound(&mut self, _path: &str) -> Result<SoundId> {
+        Ok(self.sounds.insert_silent())
+    }
+}
+
+/// Glyphs of the placeholder font in frame order, `#` marks a set pixel
+#[rustfmt::skip]
+const PLACEHOLDER_FONT: [(char, [&str; 7]); 50] = [
+    (' ', [".....", ".....", ".....", ".....", ".....", ".....", "....."]),
+    ('0', [".###.", "#...#", "#..##", "#.#.#", "##..#", "#...#", ".###."]),
+    ('1', ["..#..", ".##..", "..#..", "..#..", "..#..", "..#..", ".###."]),
+    ('2', [".###.", "#...#", "....#", "...#.", "..#..", ".#...", "#####"]),
+    ('3', ["#####", "...#.", "..#..", "...#.", "....#", "#...#", ".###."]),
+    ('4', ["...#.", "..##.", ".#.#.", "#..#.", "#####", "...#.", "...#."]),
+    ('5', ["#####", "#....", "####.", "....#", "....#", "#...#", ".###."]),
+    ('6', ["..##.", ".#...", "#....", "####.", "#...#", "#...#", ".###."]),
+    ('7', ["#####", "....#", "...#.", "..#..", ".#...", ".#...", ".#..."]),
+    ('8', [".###.", "#...#", "#...#", ".###.", "#...#", "#...#", ".###."]),
+    ('9', [".###.", "#...#", "#...#", ".####", "....#", "...#.", ".##.."]),
+    ('A', [".###.", "#...#", "#...#", "#####", "#...#", "#...#", "#...#"]),
+    ('B', ["####.", "#...#", "#...#", "####.", "#...#", "#...#", "####."]),
+    ('C', [".###.", "#...#", "#....", "#....", "#....", "#...#", ".###."]),
+    ('D', ["###..", "#..#.", "#...#", "#...#", "#...#", "#..#.", "###.."]),
+    ('E', ["#####", "#....", "#....", "####.", "#....", "#....", "#####"]),
+    ('F', ["#####", "#....", "#....", "####.", "#....", "#....", "#...."]),
+    ('G', [".###.", "#...#", "#....", "#.###", "#...#", "#...#", ".####"]),
+    ('H', ["#...#", "#...#", "#...#", "#####", "#...#", "#...#", "#...#"]),
+    ('I', [".###.", "..#..", "..#..", "..#..", "..#..", "..#..", ".###."]),
+    ('J', ["..###", "...#.", "...#.", "...#.", "...#.", "#..#.", ".##.."]),
+    ('K', ["#...#", "#..#.", "#.#..", "##...", "#.#..", "#..#.", "#...#"]),
+    ('L', ["#....", "#....", "#....", "#....", "#....", "#....", "#####"]),
+    ('M', ["#...#", "##.##", "#.#.#", "#.#.#", "#...#", "#...#", "#...#"]),
+    ('N', ["#...#", "#...#", "##..#", "#.#.#", "#..##", "#...#", "#...#"]),
+    ('O', [".###.", "#...#", "#...#", "#...#", "#...#", "#...#", ".###."]),
+    ('P', ["####.", "#...#", "#...#", "####.", "#....", "#....", "#...."]),
+    ('Q', [".###.", "#...#", "#...#", "#...#", "#.#.#", "#..#.", ".##.#"]),
+    ('R', ["####.", "#...#", "#...#", "####.", "#.#..", "#..#.", "#...#"]),
+    ('S', [".####", "#....", "#....", ".###.", "....#", "....#", "####."]),
+    ('T', ["#####", "..#..", "..#..", "..#..", "..#..", "..#..", "..#.."]),
+    ('U', ["#...#", "#...#", "#...#", "#...#", "#...#", "#...#", ".###."]),
+    ('V', ["#...#", "#...#", "#...#", "#...#", "#...#", ".#.#.", "..#.."]),
+    ('W', ["#...#", "#...#", "#...#", "#.#.#", "#.#.#", "#.#.#", ".#.#."]),
+    ('X', ["#...#", "#...#", ".#.#.", "..#..", ".#.#.", "#...#", "#...#"]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#..", "..#.."]),
+    ('Z', ["#####", "....#", "...#.", "..#..", ".#...", "#....", "#####"]),
+    ('.', [".....", ".....", ".....", ".....", ".....", ".##..", ".##.."]),
+    (',', [".....", ".....", ".....", ".....", ".##..", "..#..", ".#..."]),
+    (':', [".....", ".##..", ".##..", ".....", ".##..", ".##..", "....."]),
+    ('!', ["..#..", "..#..", "..#..", "..#..", "..#..", ".....", "..#.."]),
+    ('?', [".###.", "#...#", "....#", "...#.", "..#..", ".....", "..#.."]),
+    ('-', [".....", ".....", ".....", "#####", ".....", ".....", "....."]),
+    ('+', [".....", "..#..", "..#..", "#####", "..#..", "..#..", "....."]),
+    ('/', [".....", "....#", "...#.", "..#..", ".#...", "#....", "....."]),
+    ('\'', ["..#..", "..#..", ".#...", ".....", ".....", ".....", "....."]),
+    ('(', ["...#.", "..#..", ".#...", ".#...", ".#...", "..#..", "...#."]),
+    (')', [".#...", "..#..", "...#.", "...#.", "...#.", "..#..", ".#..."]),
+    ('%', ["##...", "##..#", "...#.", "..#..", ".#...", "#..##", "...##"]),
+    ('=', [".....", ".....", "#####", ".....", "#####", ".....", "....."]),
+];
@@ -0,0 +1,195 @@
+use std::sync::Arc;
+
+use specs::{Component, DenseVecStorage, Entity};
+
+use crate::{
+    resource::timing::{overshoot, TIME_TOLERANCE},
+    state_machine::{Parameters, StateMachine, Transition},
+};
+
+/// Drives the frame and scale of the sprite by an animation state machine
+pub struct AnimationStateComponent {
+    pub machine: Arc<StateMachine>,
+    /// Entity the parameters are taken from, the animated entity itself without one
+    pub parameter_source: Option<Entity>,
+    state: usize,
+    /// Remaining time until the transitions of the state are checked, in s
+    remaining: f32,
+}
+
+impl AnimationStateComponent {
+    pub fn new(machine: Arc<StateMachine>) -> Self {
+        Self {
+            state: machine.initial,
+            machine,
+            parameter_source: None,
+            remaining: 0.0,
+        }
+    }
+
+    pub fn with_parameter_source(mut self, entity: Entity) -> Self {
+        self.parameter_source = Some(entity);
+        self
+    }
+
+    pub fn state_name(&self) -> &str {
+        &self.machine.states[self.state].name
+    }
+
+    pub fn frame(&self) -> Option<usize> {
+        self.machine.states[self.state].frame
+    }
+
+    pub fn scale(&self) -> Option<f32> {
+        self.machine.states[self.state].scale
+    }
+
+    /// Advance by `dt` seconds and take the first transition whose conditions hold
+    pub fn update(&mut self, dt: f32, parameters: &Parameters) {
+        let machine = self.machine.clone();
+        let taken = |transitions: &[Transition]| {
+            transitions
+                .iter()
+                .find(|transition| transition.holds(parameters))
+                .map(|transition| transition.to)
+        };
+
+        if let Some(state) = taken(&machine.any_state) {
+            self.state = state;
+            self.remaining = machine.states[state].duration;
+            return;
+        }
+
+        // States waiting for a condition only start over when it holds
+        if self.remaining > 0.0 {
+            self.remaining -= dt;
+        }
+        if self.remaining <= TIME_TOLERANCE {
+            match taken(&machine.states[self.state].transitions) {
+                Some(state) => {
+                    // Time spent beyond the duration counts towards the next state, so the
+                    // durations don't depend on the tick rate
+                    self.state = state;
+                    self.remaining = machine.states[state].duration + overshoot(self.remaining);
+                }
+                None => self.remaining = 0.0,
+            }
+        }
+    }
+}
+
+impl Component for AnimationStateComponent {
+    type Storage = DenseVecStorage<Self>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state_machine::StateMachineLibrary;
+
+    const TICK: f32 = 1.0 / 60.0;
+
+    fn machine(name: &str) -> Arc<StateMachine> {
+        StateMachineLibrary::load("data/animations.ron")
+            .unwrap()
+            .get(name)
+            .unwrap()
+    }
+
+    /// Frame after every tick of `dt`, with the parameters of each tick
+    fn frames(dt: f32, parameters: &[Parameters]) -> Vec<usize> {
+        let mut component = AnimationStateComponent::new(machine("player_banking"));
+        parameters
+            .iter()
+            .map(|parameters| {
+                component.update(dt, parameters);
+                component.frame().unwrap()
+            })
+            .collect()
+    }
+
+    /// `ticks` ticks of the given acceleration and velocity
+    fn moving(ax: f32, vx: f32, ticks: usize) -> Vec<Parameters> {
+        let parameters = Parameters {
+            ax,
+            vx,
+            ..Parameters::default()
+        };
+        vec![parameters; ticks]
+    }
+
+    #[test]
+    fn banking_steps_every_two_ticks() {
+        let parameters = [
+            moving(-1.0, -10.0, 8),
+            moving(1.0, -5.0, 4),
+            moving(0.0, 0.0, 4),
+            moving(1.0, 10.0, 8),
+            moving(0.0, 0.0, 8),
+        ]
+        .concat();
+
+        #[rustfmt::skip]
+        let expected = [
+            // Banking left, holding the last step
+            1, 1, 2, 2, 3, 3, 3, 3,
+            // Turning right while still moving left
+            2, 2, 1, 1,
+            // Standing still
+            0, 0, 0, 0,
+            // Banking right
+            4, 4, 5, 5, 6, 6, 6, 6,
+            // Back to neutral once stopped
+            5, 5, 4, 4, 0, 0, 0, 0,
+        ];
+        assert_eq!(frames(TICK, &parameters), expected);
+    }
+
+    #[test]
+    fn banking_takes_the_same_time_at_higher_tick_rates() {
+        let parameters = [moving(-1.0, -10.0, 12), moving(0.0, 0.0, 12)].concat();
+        let doubled: Vec<Parameters> = parameters
+            .iter()
+            .flat_map(|parameters| [*parameters; 2])
+            .collect();
+
+        let at_60_hz: Vec<usize> = frames(TICK, &parameters)
+            .into_iter()
+            .flat_map(|frame| [frame; 2])
+            .collect();
+        assert_eq!(frames(TICK / 2.0, &doubled), at_60_hz);
+    }
+
+    #[test]
+    fn glow_flares_up_for_three_ticks_per_step() {
+        let mut component = AnimationStateComponent::new(machine("ion_cannon_glow"));
+        let fired = Parameters {
+            fired: true,
+            ..Parameters::default()
+        };
+
+        let scales: Vec<f32> = (0..20)
+            .map(|tick| {
+                let parameters = if tick == 0 {
+                    fired
+                } else {
+                    Parameters::default()
+                };
+                component.update(TICK, &parameters);
+                component.scale().unwrap()
+            })
+            .collect();
+
+        #[rustfmt::skip]
+        let expected = [
+            1.0, 1.0, 1.0, 1.1, 1.1, 1.1, 1.2, 1.2, 1.2,
+            1.1, 1.1, 1.1, 1.0, 1.0, 1.0, 0.9, 0.9, 0.9,
+            0.5, 0.5,
+        ];
+        assert_eq!(scales, expected);
+
+        // Firing again restarts the flare
+        component.update(TICK, &fired);
+        assert_eq!(component.state_name(), "fire_1");
+    }
+}
@@ -0,0 +1,26 @@
+use specs::{Component, VecStorage};
+
+/// What happens to an entity when it reaches the edges of the playfield
+#[derive(Debug, Copy, Clone)]
+pub enum BoundsPolicy {
+    /// Keep the bounding box inside the playfield, at least `margin` pixels away from its edges
+    Clamp { margin: f32 },
+    /// Delete the entity once its bounding box has completely left the playfield
+    Despawn,
+}
+
+/// Extent of an entity (centered around its position), used to keep it within the playfield
+pub struct BoundingBoxComponent {
+    pub dimensions: (f32, f32),
+    pub policy: BoundsPolicy,
+}
+
+impl BoundingBoxComponent {
+    pub fn new(dimensions: (f32, f32), policy: BoundsPolicy) -> Self {
+        Self { dimensions, policy }
+    }
+}
+
+impl Component for BoundingBoxComponent {
+    type Storage = VecStorage<Self>;
+}
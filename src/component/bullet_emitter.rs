@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use specs::{Component, HashMapStorage};
+
+use crate::{
+    component::hitbox::HitboxShape,
+    pattern::{BulletPattern, EmitterState},
+    sprite::SpriteId,
+};
+
+/// Enemy weapon firing a bullet pattern
+pub struct BulletEmitterComponent {
+    pub pattern: Arc<BulletPattern>,
+    pub state: EmitterState,
+    /// Bullets are spawned at this offset from the position of the entity
+    pub offset: (f32, f32),
+    pub bullet_sprite: SpriteId,
+    pub bullet_hitbox: HitboxShape,
+    pub bullet_damage: u32,
+    /// The weapon fired this tick
+    pub fired: bool,
+}
+
+impl BulletEmitterComponent {
+    pub fn new(
+        pattern: Arc<BulletPattern>,
+        bullet_sprite: SpriteId,
+        bullet_hitbox: HitboxShape,
+        bullet_damage: u32,
+    ) -> Self {
+        Self {
+            state: EmitterState::new(&pattern),
+            pattern,
+            offset: (0.0, 0.0),
+            bullet_sprite,
+            bullet_hitbox,
+            bullet_damage,
+            fired: false,
+        }
+    }
+
+    pub fn with_offset(mut self, offset: (f32, f32)) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+impl Component for BulletEmitterComponent {
+    type Storage = HashMapStorage<Self>;
+}
@@ -0,0 +1,16 @@
+use specs::{Component, VecStorage};
+
+/// Damage dealt on collision, the entity (e.g. a bullet) is used up by the hit
+pub struct DamageComponent {
+    pub damage: u32,
+}
+
+impl DamageComponent {
+    pub fn new(damage: u32) -> Self {
+        Self { damage }
+    }
+}
+
+impl Component for DamageComponent {
+    type Storage = VecStorage<Self>;
+}
@@ -0,0 +1,19 @@
+use std::sync::Arc;
+
+use specs::{Component, HashMapStorage};
+
+use crate::{particle::EmitterType, sound::SoundId, sprite::SpriteId};
+
+/// Effect shown and played when the entity is destroyed
+pub struct ExplosionComponent {
+    pub sprite: SpriteId,
+    pub sound: SoundId,
+    /// Time the explosion stays visible in s
+    pub duration: f32,
+    /// Particles emitted at the position of the entity
+    pub particles: Option<Arc<EmitterType>>,
+}
+
+impl Component for ExplosionComponent {
+    type Storage = HashMapStorage<Self>;
+}
@@ -0,0 +1,20 @@
+use specs::{Component, VecStorage};
+
+pub struct HealthComponent {
+    /// Remaining hit points, the entity is destroyed once they reach zero
+    pub health: u32,
+}
+
+impl HealthComponent {
+    pub fn new(health: u32) -> Self {
+        Self { health }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.health == 0
+    }
+}
+
+impl Component for HealthComponent {
+    type Storage = VecStorage<Self>;
+}
@@ -0,0 +1,177 @@
+use std::ops::BitOr;
+
+use specs::{Component, VecStorage};
+
+/// Set of collision layers, used as layer (what an entity is) and mask (what it collides with)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CollisionLayers(u32);
+
+impl CollisionLayers {
+    pub const NONE: Self = Self(0);
+    pub const PLAYER: Self = Self(1 << 0);
+    pub const PLAYER_BULLETS: Self = Self(1 << 1);
+    pub const ENEMIES: Self = Self(1 << 2);
+    pub const ENEMY_BULLETS: Self = Self(1 << 3);
+
+    pub fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl BitOr for CollisionLayers {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum HitboxShape {
+    /// Axis aligned box, centered around the hitbox position
+    Aabb {
+        width: f32,
+        height: f32,
+    },
+    Circle {
+        radius: f32,
+    },
+}
+
+impl HitboxShape {
+    /// Width and height of the smallest box containing the shape
+    pub fn dimensions(&self) -> (f32, f32) {
+        match *self {
+            HitboxShape::Aabb { width, height } => (width, height),
+            HitboxShape::Circle { radius } => (2.0 * radius, 2.0 * radius),
+        }
+    }
+}
+
+pub struct HitboxComponent {
+    pub shape: HitboxShape,
+    /// Offset of the hitbox center relative to the entity position
+    pub offset: (f32, f32),
+    pub layer: CollisionLayers,
+    pub mask: CollisionLayers,
+    /// Only solid pixels of the current sprite frame collide, the shape is used as broadphase
+    pub pixel_perfect: bool,
+}
+
+impl HitboxComponent {
+    pub fn new(shape: HitboxShape, layer: CollisionLayers, mask: CollisionLayers) -> Self {
+        Self {
+            shape,
+            offset: (0.0, 0.0),
+            layer,
+            mask,
+            pixel_perfect: false,
+        }
+    }
+
+    pub fn with_offset(mut self, offset: (f32, f32)) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn with_pixel_perfect(mut self) -> Self {
+        self.pixel_perfect = true;
+        self
+    }
+
+    /// Center of the hitbox for an entity at (x, y)
+    pub fn center(&self, x: f32, y: f32) -> (f32, f32) {
+        (x + self.offset.0, y + self.offset.1)
+    }
+
+    /// Bounding box (x_min, y_min, x_max, y_max) of the hitbox for an entity at (x, y)
+    pub fn bounds(&self, x: f32, y: f32) -> (f32, f32, f32, f32) {
+        let (cx, cy) = self.center(x, y);
+        let (width, height) = self.shape.dimensions();
+        (
+            cx - width / 2.0,
+            cy - height / 2.0,
+            cx + width / 2.0,
+            cy + height / 2.0,
+        )
+    }
+
+    /// Whether `point` lies within the hitbox of an entity at `position`
+    pub fn contains(&self, position: (f32, f32), point: (f32, f32)) -> bool {
+        let (x, y) = self.center(position.0, position.1);
+        let (dx, dy) = (point.0 - x, point.1 - y);
+
+        match self.shape {
+            HitboxShape::Aabb { width, height } => {
+                dx.abs() * 2.0 <= width && dy.abs() * 2.0 <= height
+            }
+            HitboxShape::Circle { radius } => dx * dx + dy * dy <= radius * radius,
+        }
+    }
+
+    /// Whether collisions between the two hitboxes are of interest to either of them
+    pub fn interacts_with(&self, other: &HitboxComponent) -> bool {
+        self.mask.intersects(other.layer) || other.mask.intersects(self.layer)
+    }
+
+    /// Whether this hitbox at `position` overlaps `other` at `other_position`
+    pub fn overlaps(
+        &self,
+        position: (f32, f32),
+        other: &HitboxComponent,
+        other_position: (f32, f32),
+    ) -> bool {
+        let (x, y) = self.center(position.0, position.1);
+        let (other_x, other_y) = other.center(other_position.0, other_position.1);
+
+        match (self.shape, other.shape) {
+            (
+                HitboxShape::Aabb { width, height },
+                HitboxShape::Aabb {
+                    width: other_width,
+                    height: other_height,
+                },
+            ) => {
+                (x - other_x).abs() * 2.0 < width + other_width
+                    && (y - other_y).abs() * 2.0 < height + other_height
+            }
+            (
+                HitboxShape::Circle { radius },
+                HitboxShape::Circle {
+                    radius: other_radius,
+                },
+            ) => {
+                let (dx, dy) = (x - other_x, y - other_y);
+                dx * dx + dy * dy < (radius + other_radius) * (radius + other_radius)
+            }
+            (HitboxShape::Aabb { width, height }, HitboxShape::Circle { radius }) => {
+                Self::box_overlaps_circle((x, y), (width, height), (other_x, other_y), radius)
+            }
+            (HitboxShape::Circle { radius }, HitboxShape::Aabb { width, height }) => {
+                Self::box_overlaps_circle((other_x, other_y), (width, height), (x, y), radius)
+            }
+        }
+    }
+
+    fn box_overlaps_circle(
+        center: (f32, f32),
+        dimensions: (f32, f32),
+        circle_center: (f32, f32),
+        radius: f32,
+    ) -> bool {
+        // Closest point of the box to the circle center
+        let closest_x = circle_center
+            .0
+            .clamp(center.0 - dimensions.0 / 2.0, center.0 + dimensions.0 / 2.0);
+        let closest_y = circle_center
+            .1
+            .clamp(center.1 - dimensions.1 / 2.0, center.1 + dimensions.1 / 2.0);
+
+        let (dx, dy) = (circle_center.0 - closest_x, circle_center.1 - closest_y);
+        dx * dx + dy * dy < radius * radius
+    }
+}
+
+impl Component for HitboxComponent {
+    type Storage = VecStorage<Self>;
+}
@@ -0,0 +1,11 @@
+use specs::{Component, VecStorage};
+
+/// Entity is deleted once its lifetime has run out
+pub struct LifetimeComponent {
+    /// Remaining time in s
+    pub remaining: f32,
+}
+
+impl Component for LifetimeComponent {
+    type Storage = VecStorage<Self>;
+}
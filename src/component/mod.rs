@@ -1,7 +1,69 @@
+use specs::{Join, World, WorldExt};
+
+pub mod animation_state;
+pub mod bounding_box;
+pub mod bullet_emitter;
 pub mod bullet_physics;
-pub mod player_animation;
+pub mod damage;
+pub mod explosion;
+pub mod health;
+pub mod hitbox;
+pub mod lifetime;
+pub mod particle_emitter;
+pub mod path_follow;
 pub mod player_physics;
 pub mod player_weapon;
 pub mod position;
+pub mod score;
 pub mod sprite;
+pub mod sprite_animation;
+pub mod text;
 pub mod track_position;
+
+/// Passes every component type with its name in the debug overlay to `$callback`, the single
+/// list components are registered and counted from
+macro_rules! with_components {
+    ($callback:ident) => {
+        $callback! {
+            animation_state::AnimationStateComponent => "ANIMATION STATE",
+            bounding_box::BoundingBoxComponent => "BOUNDING BOX",
+            bullet_emitter::BulletEmitterComponent => "BULLET EMITTER",
+            bullet_physics::BulletPhysicsComponent => "BULLET PHYSICS",
+            damage::DamageComponent => "DAMAGE",
+            explosion::ExplosionComponent => "EXPLOSION",
+            health::HealthComponent => "HEALTH",
+            hitbox::HitboxComponent => "HITBOX",
+            lifetime::LifetimeComponent => "LIFETIME",
+            particle_emitter::ParticleEmitterComponent => "PARTICLE EMITTER",
+            path_follow::PathFollowComponent => "PATH FOLLOW",
+            player_physics::PlayerPhysicsComponent => "PLAYER PHYSICS",
+            player_weapon::PlayerWeaponComponent => "PLAYER WEAPON",
+            position::PositionComponent => "POSITION",
+            score::ScoreComponent => "SCORE",
+            sprite::SpriteComponent => "SPRITE",
+            sprite_animation::SpriteAnimationComponent => "SPRITE ANIMATION",
+            text::TextComponent => "TEXT",
+            track_position::TrackPositionComponent => "TRACK POSITION",
+        }
+    };
+}
+
+/// Register the storages of all components
+pub fn register_all(world: &mut World) {
+    macro_rules! register {
+        ($($component:ty => $name:literal,)*) => {
+            $(world.register::<$component>();)*
+        };
+    }
+    with_components!(register);
+}
+
+/// Number of entities with each component, by name
+pub fn count_all(world: &World) -> Vec<(&'static str, usize)> {
+    macro_rules! count {
+        ($($component:ty => $name:literal,)*) => {
+            vec![$(($name, world.read_storage::<$component>().join().count()),)*]
+        };
+    }
+    with_components!(count)
+}
@@ -0,0 +1,283 @@
+use std::sync::Arc;
+
+use specs::{Component, DenseVecStorage};
+
+use crate::particle::{EmitterType, Particle, Rng};
+
+/// Emits particles at the position of the entity, the particles are simulated and rendered as
+/// part of the emitter instead of being entities of their own
+pub struct ParticleEmitterComponent {
+    pub emitter: Arc<EmitterType>,
+    /// Particles alive, in world coordinates
+    pub particles: Vec<Particle>,
+    /// Delete the entity once the emitter is finished
+    pub despawn_when_finished: bool,
+    /// Time since the emitter started in s
+    elapsed: f32,
+    /// Particles owed by the emission rate, including fractions
+    pending: f32,
+    started: bool,
+    rng: Rng,
+}
+
+impl ParticleEmitterComponent {
+    /// Emitter with a random generator seeded by `seed`, equal seeds emit equal particles
+    pub fn new(emitter: Arc<EmitterType>, seed: u64) -> Self {
+        Self {
+            emitter,
+            particles: Vec::new(),
+            despawn_when_finished: false,
+            elapsed: 0.0,
+            pending: 0.0,
+            started: false,
+            rng: Rng::new(seed),
+        }
+    }
+
+    pub fn with_despawn_when_finished(mut self) -> Self {
+        self.despawn_when_finished = true;
+        self
+    }
+
+    /// Still emitting particles at its rate, burst only emitters are done once started
+    fn is_emitting(&self) -> bool {
+        let definition = &self.emitter.definition;
+        match definition.duration {
+            _ if definition.rate == 0.0 => false,
+            Some(duration) => self.elapsed < duration,
+            None => true,
+        }
+    }
+
+    /// No particles are alive and no more will be emitted
+    pub fn is_finished(&self) -> bool {
+        self.started && !self.is_emitting() && self.particles.is_empty()
+    }
+
+    /// Advance all particles by `dt` seconds and emit new ones at `position`
+    pub fn update(&mut self, dt: f32, position: (f32, f32)) {
+        let emitter = self.emitter.clone();
+        let definition = &emitter.definition;
+        let (gravity_x, gravity_y) = definition.gravity;
+        let damping = (1.0 - definition.drag * dt).max(0.0);
+
+        self.particles.retain_mut(|particle| {
+            particle.age += dt;
+            if particle.age >= particle.lifetime {
+                return false;
+            }
+
+            particle.previous_x = particle.x;
+            particle.previous_y = particle.y;
+            particle.vx = (particle.vx + gravity_x * dt) * damping;
+            particle.vy = (particle.vy + gravity_y * dt) * damping;
+            particle.x += particle.vx * dt;
+            particle.y += particle.vy * dt;
+            true
+        });
+
+        let mut count = 0;
+        if !self.started {
+            self.started = true;
+            count += definition.burst;
+        }
+
+        if self.is_emitting() {
+            self.pending += definition.rate * dt;
+            let whole = self.pending.floor();
+            self.pending -= whole;
+            count += whole as u32;
+        }
+        self.elapsed += dt;
+
+        for _ in 0..count {
+            self.emit(position);
+        }
+    }
+
+    fn emit(&mut self, (x, y): (f32, f32)) {
+        let definition = &self.emitter.definition;
+
+        let spawn_angle = self.rng.next_f32() * std::f32::consts::TAU;
+        let spawn_distance = self.rng.next_f32() * definition.radius;
+        let x = x + definition.offset.0 + spawn_angle.cos() * spawn_distance;
+        let y = y + definition.offset.1 + spawn_angle.sin() * spawn_distance;
+
+        let angle =
+            (definition.direction + self.rng.range((-1.0, 1.0)) * definition.spread).to_radians();
+        let speed = self.rng.range(definition.speed);
+
+        self.particles.push(Particle {
+            x,
+            y,
+            previous_x: x,
+            previous_y: y,
+            vx: angle.cos() * speed,
+            vy: angle.sin() * speed,
+            age: 0.0,
+            lifetime: self.rng.range(definition.lifetime),
+        });
+    }
+}
+
+impl Component for ParticleEmitterComponent {
+    type Storage = DenseVecStorage<Self>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        assets::{Assets, HeadlessAssetLoader},
+        particle::EmitterLibrary,
+    };
+
+    /// Emitter using the placeholder particle sprite, `fields` are added to its definition
+    fn emitter(fields: &str) -> Arc<EmitterType> {
+        let assets = Assets::load(&mut HeadlessAssetLoader::new()).unwrap();
+        let content = format!(
+            r#"{{ "test": (
+                sprite: "particle_placeholder",
+                layer: AirEffects,
+                spread: 180.0,
+                speed: (10.0, 50.0),
+                {}
+            ) }}"#,
+            fields
+        );
+        EmitterLibrary::parse(&content, &assets.catalog)
+            .unwrap()
+            .get("test")
+            .unwrap()
+    }
+
+    #[test]
+    fn burst_is_emitted_once() {
+        let mut component =
+            ParticleEmitterComponent::new(emitter("burst: 5, lifetime: (10.0, 10.0)"), 1);
+
+        component.update(0.1, (0.0, 0.0));
+        assert_eq!(component.particles.len(), 5);
+        component.update(0.1, (0.0, 0.0));
+        component.update(0.1, (0.0, 0.0));
+        assert_eq!(component.particles.len(), 5);
+    }
+
+    #[test]
+    fn rate_carries_fractions_over() {
+        let mut component =
+            ParticleEmitterComponent::new(emitter("rate: 10.0, lifetime: (10.0, 10.0)"), 1);
+
+        // 2.5 particles per update
+        let counts: Vec<usize> = (0..4)
+            .map(|_| {
+                component.update(0.25, (0.0, 0.0));
+                component.particles.len()
+            })
+            .collect();
+        assert_eq!(counts, [2, 5, 7, 10]);
+    }
+
+    #[test]
+    fn burst_and_rate_add_up() {
+        let mut component = ParticleEmitterComponent::new(
+            emitter("burst: 3, rate: 4.0, lifetime: (10.0, 10.0)"),
+            1,
+        );
+
+        component.update(0.5, (0.0, 0.0));
+        assert_eq!(component.particles.len(), 5);
+        component.update(0.5, (0.0, 0.0));
+        assert_eq!(component.particles.len(), 7);
+    }
+
+    #[test]
+    fn particles_expire_after_their_lifetime() {
+        let mut component =
+            ParticleEmitterComponent::new(emitter("burst: 4, lifetime: (0.5, 0.5)"), 1);
+
+        component.update(0.25, (0.0, 0.0));
+        component.update(0.2, (0.0, 0.0));
+        assert_eq!(component.particles.len(), 4);
+        assert!(component
+            .particles
+            .iter()
+            .all(|particle| particle.age > 0.0));
+
+        component.update(0.3, (0.0, 0.0));
+        assert!(component.particles.is_empty());
+    }
+
+    #[test]
+    fn burst_only_emitter_finishes_once_its_particles_are_gone() {
+        let mut component =
+            ParticleEmitterComponent::new(emitter("burst: 3, lifetime: (0.5, 1.0)"), 1);
+        assert!(!component.is_finished());
+
+        component.update(0.1, (0.0, 0.0));
+        assert!(!component.is_finished());
+
+        for _ in 0..10 {
+            component.update(0.1, (0.0, 0.0));
+        }
+        assert!(component.particles.is_empty());
+        assert!(component.is_finished());
+    }
+
+    #[test]
+    fn rate_emitter_finishes_after_its_duration() {
+        let mut component = ParticleEmitterComponent::new(
+            emitter("rate: 10.0, duration: Some(0.5), lifetime: (0.2, 0.2)"),
+            1,
+        );
+
+        for _ in 0..5 {
+            component.update(0.1, (0.0, 0.0));
+            assert!(!component.is_finished());
+        }
+        let emitted = component.particles.len();
+        component.update(0.1, (0.0, 0.0));
+        assert!(component.particles.len() < emitted);
+
+        for _ in 0..3 {
+            component.update(0.1, (0.0, 0.0));
+        }
+        assert!(component.is_finished());
+
+        // Emitters without a duration never finish
+        let mut endless =
+            ParticleEmitterComponent::new(emitter("rate: 10.0, lifetime: (0.2, 0.2)"), 1);
+        for _ in 0..10 {
+            endless.update(0.1, (0.0, 0.0));
+        }
+        assert!(!endless.is_finished());
+    }
+
+    #[test]
+    fn equal_seeds_emit_equal_particles() {
+        let definition = emitter("burst: 10, rate: 20.0, radius: 4.0, lifetime: (0.2, 1.0)");
+        let run = |seed| {
+            let mut component = ParticleEmitterComponent::new(definition.clone(), seed);
+            for _ in 0..20 {
+                component.update(1.0 / 60.0, (100.0, 50.0));
+            }
+            component
+                .particles
+                .iter()
+                .map(|particle| {
+                    (
+                        particle.x,
+                        particle.y,
+                        particle.vx,
+                        particle.vy,
+                        particle.lifetime,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert!(!run(7).is_empty());
+        assert_eq!(run(7), run(7));
+        assert_ne!(run(7), run(8));
+    }
+}
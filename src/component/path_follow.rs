@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use specs::{Component, VecStorage};
+
+use crate::path::Path;
+
+/// Moves the entity along a path, starting at `origin`
+pub struct PathFollowComponent {
+    pub path: Arc<Path>,
+    pub origin: (f32, f32),
+    /// Time since the path was started in s
+    pub elapsed: f32,
+}
+
+impl PathFollowComponent {
+    pub fn new(path: Arc<Path>, origin: (f32, f32)) -> Self {
+        Self {
+            path,
+            origin,
+            elapsed: 0.0,
+        }
+    }
+}
+
+impl Component for PathFollowComponent {
+    type Storage = VecStorage<Self>;
+}
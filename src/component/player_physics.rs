@@ -1,7 +1,7 @@
 use specs::{Component, HashMapStorage};
 
 pub struct PlayerPhysicsComponent {
-    // Acceleration / velocity
+    // Acceleration / velocity (pixels / s^2, pixels / s)
     pub ax: f32,
     pub ay: f32,
     pub vx: f32,
@@ -12,12 +12,6 @@ pub struct PlayerPhysicsComponent {
     pub ay_max: f32,
     pub vx_max: f32,
     pub vy_max: f32,
-
-    // Bounding box (should probably be separate component)
-    pub x_min: f32,
-    pub x_max: f32,
-    pub y_min: f32,
-    pub y_max: f32,
 }
 
 impl Component for PlayerPhysicsComponent {
@@ -1,13 +1,24 @@
 use specs::{Component, HashMapStorage};
 
-use crate::{sound::SoundId, sprite::SpriteId};
+use crate::{
+    component::{hitbox::HitboxShape, sprite_animation::SpriteAnimationComponent},
+    sound::SoundId,
+    sprite::SpriteId,
+};
 
 pub struct PlayerWeaponComponent {
-    pub cooldown_reset: u32,
-    pub cooldown: u32,
+    /// Time between two shots in s
+    pub cooldown_reset: f32,
+    /// Remaining time until the weapon can fire again in s
+    pub cooldown: f32,
     pub bullet_sprite: SpriteId,
-    pub bullet_dimensions: (u32, u32),
+    pub bullet_hitbox: HitboxShape,
     pub bullet_sound: SoundId,
+    pub bullet_damage: u32,
+    /// The weapon fired this tick
+    pub fired: bool,
+    /// Animation every bullet starts with
+    pub bullet_animation: Option<SpriteAnimationComponent>,
 }
 
 impl Component for PlayerWeaponComponent {
@@ -16,17 +27,26 @@ impl Component for PlayerWeaponComponent {
 
 impl PlayerWeaponComponent {
     pub fn new(
-        cooldown: u32,
+        cooldown: f32,
         bullet_sprite: SpriteId,
-        bullet_dimensions: (u32, u32),
+        bullet_hitbox: HitboxShape,
         bullet_sound: SoundId,
+        bullet_damage: u32,
     ) -> Self {
         Self {
             cooldown_reset: cooldown,
-            cooldown: 0,
+            cooldown: 0.0,
             bullet_sprite,
-            bullet_dimensions,
+            bullet_hitbox,
             bullet_sound,
+            bullet_damage,
+            fired: false,
+            bullet_animation: None,
         }
     }
+
+    pub fn with_bullet_animation(mut self, animation: Option<SpriteAnimationComponent>) -> Self {
+        self.bullet_animation = animation;
+        self
+    }
 }
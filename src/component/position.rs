@@ -44,6 +44,12 @@ impl PositionComponent {
         self.y_n = y;
     }
 
+    /// Restrict the current position to the given area, the previous position is kept as is
+    pub fn clamp(&mut self, x_min: f32, x_max: f32, y_min: f32, y_max: f32) {
+        self.x_n = self.x_n.clamp(x_min, x_max);
+        self.y_n = self.y_n.clamp(y_min, y_max);
+    }
+
     pub fn x(&self) -> f32 {
         self.x_n
     }
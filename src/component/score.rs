@@ -0,0 +1,10 @@
+use specs::{Component, HashMapStorage};
+
+/// Points the player gets for destroying the entity
+pub struct ScoreComponent {
+    pub points: u32,
+}
+
+impl Component for ScoreComponent {
+    type Storage = HashMapStorage<Self>;
+}
@@ -2,11 +2,40 @@ use specs::{Component, VecStorage};
 
 use crate::{sprite::SpriteId, system::render::Layer};
 
+/// How a sprite is combined with what is already drawn
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum SpriteBlendMode {
+    /// Drawn over, weighted by its alpha
+    #[default]
+    Alpha,
+    /// Added to, brightening it, e.g. for glows and flashes
+    Additive,
+    /// Multiplied with, darkening it, e.g. for shadows
+    Modulate,
+}
+
 pub struct SpriteComponent {
     pub sprite: SpriteId,
     pub layer: Layer,
+    /// Order within the layer, higher values are rendered on top
+    pub z_order: i32,
     pub current_frame_idx: usize,
     pub scale_factor: f32,
+    /// Point the sprite is rotated around, in unscaled pixels from the top left of the frame, the
+    /// center of the frame without one
+    pub pivot: Option<(f32, f32)>,
+    pub flip_horizontal: bool,
+    pub flip_vertical: bool,
+    /// Color and alpha the frame is modulated with
+    pub color: (u8, u8, u8, u8),
+    pub blend_mode: SpriteBlendMode,
+    /// Rotation in degrees clockwise
+    pub rotation: f32,
+    /// From 0.0 (invisible) to 1.0, multiplied with the alpha of `color`
+    pub opacity: f32,
+    /// Rotation and opacity at the end of the previous tick
+    previous_rotation: f32,
+    previous_opacity: f32,
 }
 
 impl SpriteComponent {
@@ -14,8 +43,18 @@ impl SpriteComponent {
         Self {
             sprite: sprite_id,
             layer,
+            z_order: 0,
             current_frame_idx: 0,
             scale_factor: 1.0,
+            pivot: None,
+            flip_horizontal: false,
+            flip_vertical: false,
+            color: (255, 255, 255, 255),
+            blend_mode: SpriteBlendMode::Alpha,
+            rotation: 0.0,
+            opacity: 1.0,
+            previous_rotation: 0.0,
+            previous_opacity: 1.0,
         }
     }
 
@@ -23,6 +62,70 @@ impl SpriteComponent {
         self.scale_factor = scale_factor;
         self
     }
+
+    pub fn with_z_order(mut self, z_order: i32) -> Self {
+        self.z_order = z_order;
+        self
+    }
+
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self.previous_rotation = rotation;
+        self
+    }
+
+    pub fn with_pivot(mut self, x: f32, y: f32) -> Self {
+        self.pivot = Some((x, y));
+        self
+    }
+
+    pub fn with_flip(mut self, horizontal: bool, vertical: bool) -> Self {
+        self.flip_horizontal = horizontal;
+        self.flip_vertical = vertical;
+        self
+    }
+
+    pub fn with_color(mut self, r: u8, g: u8, b: u8, a: u8) -> Self {
+        self.color = (r, g, b, a);
+        self
+    }
+
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self.previous_opacity = opacity;
+        self
+    }
+
+    pub fn with_blend_mode(mut self, blend_mode: SpriteBlendMode) -> Self {
+        self.blend_mode = blend_mode;
+        self
+    }
+
+    /// Keep rotation and opacity of the tick that ends, to interpolate from them
+    pub fn store_previous(&mut self) {
+        self.previous_rotation = self.rotation;
+        self.previous_opacity = self.opacity;
+    }
+
+    pub fn previous_rotation(&self) -> f32 {
+        self.previous_rotation
+    }
+
+    pub fn previous_opacity(&self) -> f32 {
+        self.previous_opacity
+    }
+
+    /// Rotation between the previous (`alpha` = 0.0) and the current tick (`alpha` = 1.0),
+    /// turning the shorter way
+    pub fn interpolated_rotation(&self, alpha: f32) -> f32 {
+        let delta = (self.rotation - self.previous_rotation + 180.0).rem_euclid(360.0) - 180.0;
+        self.previous_rotation + delta * alpha
+    }
+
+    /// Opacity between the previous (`alpha` = 0.0) and the current tick (`alpha` = 1.0)
+    pub fn interpolated_opacity(&self, alpha: f32) -> f32 {
+        (self.opacity * alpha + self.previous_opacity * (1.0 - alpha)).clamp(0.0, 1.0)
+    }
 }
 
 impl Component for SpriteComponent {
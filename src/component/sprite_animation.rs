@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use specs::{Component, DenseVecStorage};
+
+use crate::animation::{AnimationClip, AnimationClips, Playback};
+
+/// Plays the named animation clips of a sprite, the frame is applied to the `SpriteComponent`
+#[derive(Clone)]
+pub struct SpriteAnimationComponent {
+    pub clips: Arc<AnimationClips>,
+    /// Playback speed, 1.0 plays the clip at its defined frame durations
+    pub speed: f32,
+    clip_name: String,
+    clip: Arc<AnimationClip>,
+    playback: Playback,
+}
+
+impl SpriteAnimationComponent {
+    /// Animation playing the clip `name`, `None` if the sprite has no such clip
+    pub fn new(clips: Arc<AnimationClips>, name: &str) -> Option<Self> {
+        let clip = clips.get(name)?;
+        Some(Self {
+            clips,
+            speed: 1.0,
+            clip_name: name.to_string(),
+            clip,
+            playback: Playback::default(),
+        })
+    }
+
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Switch to the clip `name` from its first frame, keeps playing if it is already the current
+    /// clip. Returns false if the sprite has no such clip.
+    pub fn play(&mut self, name: &str) -> bool {
+        if self.clip_name == name {
+            return true;
+        }
+        match self.clips.get(name) {
+            Some(clip) => {
+                self.clip_name = name.to_string();
+                self.clip = clip;
+                self.playback = Playback::default();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn clip_name(&self) -> &str {
+        &self.clip_name
+    }
+
+    pub fn current_frame(&self) -> usize {
+        self.playback.frame(&self.clip)
+    }
+
+    /// A clip played once has reached its last frame
+    pub fn is_finished(&self) -> bool {
+        self.playback.is_finished()
+    }
+
+    pub fn advance(&mut self, dt: f32) {
+        self.playback.advance(&self.clip, dt * self.speed);
+    }
+}
+
+impl Component for SpriteAnimationComponent {
+    type Storage = DenseVecStorage<Self>;
+}
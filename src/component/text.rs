@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use specs::{Component, DenseVecStorage};
+
+use crate::{
+    font::{Alignment, BitmapFont},
+    system::render::Layer,
+};
+
+/// Text drawn in the game world, vertically centered on the position of the entity
+pub struct TextComponent {
+    pub text: String,
+    pub font: Arc<BitmapFont>,
+    pub alignment: Alignment,
+    /// Color the (white) glyphs are modulated with
+    pub color: (u8, u8, u8),
+    pub layer: Layer,
+    /// Order within the layer, higher values are rendered on top
+    pub z_order: i32,
+    pub scale_factor: f32,
+}
+
+impl TextComponent {
+    pub fn new(text: impl Into<String>, font: Arc<BitmapFont>, layer: Layer) -> Self {
+        Self {
+            text: text.into(),
+            font,
+            alignment: Alignment::Center,
+            color: (255, 255, 255),
+            layer,
+            z_order: 0,
+            scale_factor: 1.0,
+        }
+    }
+
+    pub fn with_alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn with_color(mut self, r: u8, g: u8, b: u8) -> Self {
+        self.color = (r, g, b);
+        self
+    }
+
+    pub fn with_z_order(mut self, z_order: i32) -> Self {
+        self.z_order = z_order;
+        self
+    }
+
+    pub fn with_scale_factor(mut self, scale_factor: f32) -> Self {
+        self.scale_factor = scale_factor;
+        self
+    }
+}
+
+impl Component for TextComponent {
+    type Storage = DenseVecStorage<Self>;
+}
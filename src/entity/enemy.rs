@@ -0,0 +1,169 @@
+use std::{collections::HashMap, fs, path::Path as FilePath, sync::Arc};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use specs::{Builder, Entity};
+
+use crate::{
+    component::{
+        animation_state::AnimationStateComponent,
+        bullet_emitter::BulletEmitterComponent,
+        explosion::ExplosionComponent,
+        health::HealthComponent,
+        hitbox::{CollisionLayers, HitboxComponent, HitboxShape},
+        path_follow::PathFollowComponent,
+        position::PositionComponent,
+        score::ScoreComponent,
+        sprite::SpriteComponent,
+    },
+    particle::EmitterType,
+    path::Path,
+    pattern::BulletPattern,
+    sound::SoundId,
+    sprite::{SpriteDescription, SpriteId},
+    state_machine::StateMachine,
+    system::render::Layer,
+};
+
+/// Time an explosion stays visible in s
+const EXPLOSION_DURATION: f32 = 0.25;
+
+/// Enemy bullets are small round projectiles
+const BULLET_HITBOX: HitboxShape = HitboxShape::Circle { radius: 4.0 };
+const BULLET_DAMAGE: u32 = 1;
+
+/// Weapon of an enemy type as written in the enemy file
+#[derive(Debug, Clone, Deserialize)]
+pub struct WeaponDefinition {
+    pub pattern: String,
+    pub bullet_sprite: String,
+}
+
+/// Enemy type as written in the enemy file, assets are referenced by name
+#[derive(Debug, Clone, Deserialize)]
+pub struct EnemyDefinition {
+    pub sprite: String,
+    pub health: u32,
+    /// Points for destroying the enemy
+    #[serde(default)]
+    pub score: u32,
+    #[serde(default)]
+    pub weapon: Option<WeaponDefinition>,
+    pub explosion_sprite: String,
+    pub explosion_sound: String,
+    /// Name of a particle emitter
+    #[serde(default)]
+    pub explosion_particles: Option<String>,
+    /// Name of an animation state machine
+    #[serde(default)]
+    pub animation: Option<String>,
+}
+
+/// All enemy types, by name
+#[derive(Default)]
+pub struct EnemyLibrary {
+    enemies: HashMap<String, EnemyDefinition>,
+}
+
+impl EnemyLibrary {
+    /// Load enemy types from a RON file mapping names to enemy types
+    pub fn load<P: AsRef<FilePath>>(file: P) -> Result<Self> {
+        let file = file.as_ref();
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read enemy file {}", file.display()))?;
+
+        Self::parse(&content).with_context(|| format!("Invalid enemy file {}", file.display()))
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        Ok(Self {
+            enemies: ron::from_str(content)?,
+        })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&EnemyDefinition> {
+        self.enemies.get(name)
+    }
+}
+
+/// Weapon of an enemy type with all references resolved
+#[derive(Debug, Clone)]
+pub struct EnemyWeapon {
+    pub pattern: Arc<BulletPattern>,
+    pub bullet_sprite: SpriteId,
+}
+
+/// Enemy type with all references resolved, ready to be spawned
+#[derive(Debug, Clone)]
+pub struct EnemyType {
+    pub sprite: SpriteId,
+    pub sprite_description: SpriteDescription,
+    pub health: u32,
+    pub score: u32,
+    pub weapon: Option<EnemyWeapon>,
+    pub explosion_sprite: SpriteId,
+    pub explosion_sound: SoundId,
+    pub explosion_particles: Option<Arc<EmitterType>>,
+    pub animation: Option<Arc<StateMachine>>,
+}
+
+pub struct Enemy;
+
+impl Enemy {
+    /// Build an enemy at (x, y), following `path` (starting at the same position) if given
+    pub fn create_enemy<B: Builder>(
+        builder: B,
+        enemy_type: &EnemyType,
+        x: f32,
+        y: f32,
+        path: Option<Arc<Path>>,
+    ) -> Entity {
+        let radius = enemy_type
+            .sprite_description
+            .frame_dimensions
+            .0
+            .min(enemy_type.sprite_description.frame_dimensions.1) as f32
+            / 2.0;
+
+        let mut builder = builder
+            .with(SpriteComponent::new(enemy_type.sprite, Layer::AirUnits))
+            .with(PositionComponent::new(x, y))
+            .with(HitboxComponent::new(
+                HitboxShape::Circle { radius },
+                CollisionLayers::ENEMIES,
+                CollisionLayers::PLAYER | CollisionLayers::PLAYER_BULLETS,
+            ))
+            .with(HealthComponent::new(enemy_type.health))
+            .with(ExplosionComponent {
+                sprite: enemy_type.explosion_sprite,
+                sound: enemy_type.explosion_sound,
+                duration: EXPLOSION_DURATION,
+                particles: enemy_type.explosion_particles.clone(),
+            });
+
+        if enemy_type.score > 0 {
+            builder = builder.with(ScoreComponent {
+                points: enemy_type.score,
+            });
+        }
+
+        if let Some(path) = path {
+            builder = builder.with(PathFollowComponent::new(path, (x, y)));
+        }
+
+        if let Some(weapon) = &enemy_type.weapon {
+            builder = builder.with(BulletEmitterComponent::new(
+                weapon.pattern.clone(),
+                weapon.bullet_sprite,
+                BULLET_HITBOX,
+                BULLET_DAMAGE,
+            ));
+        }
+
+        if let Some(animation) = &enemy_type.animation {
+            builder = builder.with(AnimationStateComponent::new(animation.clone()));
+        }
+
+        builder.build()
+    }
+}
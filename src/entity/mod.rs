@@ -1 +1,2 @@
+pub mod enemy;
 pub mod player;
@@ -1,43 +1,81 @@
 use specs::{Builder, World, WorldExt};
 
 use crate::{
+    assets::{Assets, BULLET_SPIN_CLIP},
     component::{
-        player_animation::PlayerAnimationComponent, player_physics::PlayerPhysicsComponent,
-        player_weapon::PlayerWeaponComponent, position::PositionComponent, sprite::SpriteComponent,
+        animation_state::AnimationStateComponent,
+        bounding_box::{BoundingBoxComponent, BoundsPolicy},
+        hitbox::{CollisionLayers, HitboxComponent, HitboxShape},
+        particle_emitter::ParticleEmitterComponent,
+        player_physics::PlayerPhysicsComponent,
+        player_weapon::PlayerWeaponComponent,
+        position::PositionComponent,
+        sprite::SpriteComponent,
+        sprite_animation::SpriteAnimationComponent,
         track_position::TrackPositionComponent,
     },
-    sound::SoundId,
-    sprite::{SpriteDescription, SpriteId},
+    particle::EmitterLibrary,
+    state_machine::StateMachineLibrary,
     system::render::Layer,
-    /* FIXME: do proper delta time implementation in physics systems */
-    FRAME_RATE_GAME,
-    /* FIXME: should not be used via constant (maybe have screen width / height as resource and a bounding box component?) */
+    /* Velocities are relative to the original game resolution */
     GAME_HEIGHT, GAME_WIDTH,
 };
 
-const VX_MAX: f32 = 0.000314 * GAME_WIDTH as f32 * (1000.0 / (FRAME_RATE_GAME as f32));
-const VY_MAX: f32 = 0.000487 * GAME_HEIGHT as f32 * (1000.0 / (FRAME_RATE_GAME as f32));
-const AX_MAX: f32 = 6.286_875e-5 * GAME_WIDTH as f32 * (1000.0 / (FRAME_RATE_GAME as f32));
-const AY_MAX: f32 = 9.735e-5 * GAME_HEIGHT as f32 * (1000.0 / (FRAME_RATE_GAME as f32));
+/// Max. velocity in pixels / s
+const VX_MAX: f32 = 0.000314 * GAME_WIDTH as f32 * 1000.0;
+const VY_MAX: f32 = 0.000487 * GAME_HEIGHT as f32 * 1000.0;
+
+/// Max. acceleration in pixels / s^2 (originally tuned as velocity change per tick at 60 Hz)
+const AX_MAX: f32 = 6.286_875e-5 * GAME_WIDTH as f32 * 1000.0 * 60.0;
+const AY_MAX: f32 = 9.735e-5 * GAME_HEIGHT as f32 * 1000.0 * 60.0;
+
+/// Time between two shots of the ion cannon in s, every 11 ticks at 60 Hz
+const WEAPON_COOLDOWN: f32 = 11.0 / 60.0;
+
+/// Core of the ship, refined by the sprite mask where masks are available
+const HITBOX: HitboxShape = HitboxShape::Aabb {
+    width: 22.0,
+    height: 26.0,
+};
+
+/// Visible part of the ion cannon bullet sprite
+const BULLET_HITBOX: HitboxShape = HitboxShape::Aabb {
+    width: 14.0,
+    height: 18.0,
+};
+
+/// Hit points taken from an enemy by a single ion cannon bullet
+const BULLET_DAMAGE: u32 = 1;
+
+/// State machine banking the ship
+const BANKING_ANIMATION: &str = "player_banking";
+
+/// State machine of the glow flaring up with every shot
+const GLOW_ANIMATION: &str = "ion_cannon_glow";
+
+/// Emitter attached to the ship
+const EXHAUST_EMITTER: &str = "exhaust";
 
 pub struct Player;
 
 impl Player {
-    #[allow(clippy::too_many_arguments)]
     pub fn create_player(
         world: &mut World,
-        sprite_id: SpriteId,
-        sprite_desc: &SpriteDescription,
+        assets: &Assets,
+        animations: &StateMachineLibrary,
+        particles: &EmitterLibrary,
         x: f32,
         y: f32,
-        bullet_sprite_id: SpriteId,
-        bullet_dimensions: (u32, u32),
-        bullet_sound_id: SoundId,
-        glow_sprite_id: SpriteId,
     ) {
-        let player_entity = world
+        let sprite_desc = &assets.player_sprite_description;
+        let bullet_animation = assets
+            .catalog
+            .sprite("ion_cannon_bullet")
+            .and_then(|entry| SpriteAnimationComponent::new(entry.clips.clone(), BULLET_SPIN_CLIP));
+
+        let mut player_builder = world
             .create_entity()
-            .with(SpriteComponent::new(sprite_id, Layer::AirUnits))
+            .with(SpriteComponent::new(assets.player_sprite, Layer::AirUnits))
             .with(PositionComponent::new(x, y))
             .with(PlayerPhysicsComponent {
                 ax: 0.0,
@@ -48,35 +86,58 @@ impl Player {
                 ay_max: AY_MAX,
                 vx_max: VX_MAX,
                 vy_max: VY_MAX,
-                x_min: (25 + sprite_desc.frame_dimensions.0 / 2_usize) as f32,
-                x_max: (GAME_WIDTH - 25 - sprite_desc.frame_dimensions.0 as u32 / 2) as f32,
-                y_min: (25 + sprite_desc.frame_dimensions.1 / 2_usize) as f32,
-                y_max: (GAME_HEIGHT - 25 - sprite_desc.frame_dimensions.1 as u32 / 2) as f32,
             })
-            .with(PlayerAnimationComponent::default())
-            .with(PlayerWeaponComponent::new(
-                FRAME_RATE_GAME / 6,
-                bullet_sprite_id,
-                bullet_dimensions,
-                bullet_sound_id,
+            .with(BoundingBoxComponent::new(
+                (
+                    sprite_desc.frame_dimensions.0 as f32,
+                    sprite_desc.frame_dimensions.1 as f32,
+                ),
+                BoundsPolicy::Clamp { margin: 25.0 },
             ))
-            .build();
+            .with(
+                HitboxComponent::new(
+                    HITBOX,
+                    CollisionLayers::PLAYER,
+                    CollisionLayers::ENEMIES | CollisionLayers::ENEMY_BULLETS,
+                )
+                .with_pixel_perfect(),
+            )
+            .with(
+                PlayerWeaponComponent::new(
+                    WEAPON_COOLDOWN,
+                    assets.bullet_sprite,
+                    BULLET_HITBOX,
+                    assets.bullet_sound,
+                    BULLET_DAMAGE,
+                )
+                .with_bullet_animation(bullet_animation),
+            );
+        if let Some(banking) = animations.get(BANKING_ANIMATION) {
+            player_builder = player_builder.with(AnimationStateComponent::new(banking));
+        }
+        if let Some(exhaust) = particles.get(EXHAUST_EMITTER) {
+            player_builder = player_builder.with(ParticleEmitterComponent::new(exhaust, 0));
+        }
+        let player_entity = player_builder.build();
 
-        let glow_entity = world
+        let mut glow_builder = world
             .create_entity()
-            .with(SpriteComponent::new(glow_sprite_id, Layer::Effects).with_scale_factor(1.2))
+            // Right below the player sprite
+            .with(
+                SpriteComponent::new(assets.glow_sprite, Layer::AirUnits)
+                    .with_scale_factor(1.2)
+                    .with_z_order(-1),
+            )
             .with(PositionComponent::new(0.0, 0.0))
             .with(TrackPositionComponent {
                 tracked_entity: player_entity,
                 offset: (-0.0, -6.0),
-            })
-            .build();
-
-        // Connect glow entity to player entity
-        let mut animation = world.write_component::<PlayerAnimationComponent>();
-        let animation = animation
-            .get_mut(player_entity)
-            .expect("player entity should have an animation component");
-        animation.weapon_glow_entity = Some(glow_entity);
+            });
+        if let Some(glow) = animations.get(GLOW_ANIMATION) {
+            // Flares up when the player's weapon fires
+            glow_builder = glow_builder
+                .with(AnimationStateComponent::new(glow).with_parameter_source(player_entity));
+        }
+        glow_builder.build();
     }
 }
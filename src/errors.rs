@@ -13,4 +13,6 @@ pub enum SdlError {
     SoundLoadError(String),
     #[error("Failed to play audio sample: {0}")]
     AudioPlayError(String),
+    #[error("Failed to capture screenshot: {0}")]
+    ScreenshotError(String),
 }
@@ -0,0 +1,237 @@
+use std::{collections::HashMap, fs, path::Path, sync::Arc};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+use crate::{assets::AssetCatalog, sprite::SpriteId};
+
+/// Horizontal alignment of text relative to its position
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Deserialize)]
+pub enum Alignment {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// Font as written in the font file, sizes are in pixels
+#[derive(Debug, Clone, Deserialize)]
+struct FontDefinition {
+    /// Sprite with one frame per glyph
+    sprite: String,
+    /// Characters of the sprite frames, in frame order
+    glyphs: String,
+    /// Advance of all glyphs without their own width, the frame width if not given
+    #[serde(default)]
+    advance: Option<i32>,
+    /// Advance of single glyphs
+    #[serde(default)]
+    widths: HashMap<char, i32>,
+    /// Adjustment of the advance between two glyphs
+    #[serde(default)]
+    kerning: Vec<(char, char, i32)>,
+    /// Distance between two lines, the frame height if not given
+    #[serde(default)]
+    line_height: Option<i32>,
+    /// Glyph shown for characters the font doesn't have
+    #[serde(default)]
+    fallback: Option<char>,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Glyph {
+    frame: usize,
+    advance: i32,
+}
+
+/// Glyph of a text, relative to the position of the text
+#[derive(Debug, Copy, Clone)]
+pub struct PlacedGlyph {
+    pub frame: usize,
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Font with its glyphs taken from the frames of a sprite
+#[derive(Debug)]
+pub struct BitmapFont {
+    sprite: SpriteId,
+    glyph_size: (usize, usize),
+    glyphs: HashMap<char, Glyph>,
+    kerning: HashMap<(char, char), i32>,
+    advance: i32,
+    line_height: i32,
+    fallback: Option<char>,
+}
+
+impl BitmapFont {
+    fn from_definition(definition: &FontDefinition, catalog: &AssetCatalog) -> Result<Self> {
+        let sprite = catalog
+            .sprite(&definition.sprite)
+            .with_context(|| format!("unknown sprite \"{}\"", definition.sprite))?;
+        let (width, height) = sprite.description.frame_dimensions;
+        let advance = definition.advance.unwrap_or(width as i32);
+
+        let frames = sprite.description.number_of_frames;
+        let glyph_count = definition.glyphs.chars().count();
+        if glyph_count > frames {
+            bail!(
+                "font has {} glyphs, but sprite \"{}\" only {} frames",
+                glyph_count,
+                definition.sprite,
+                frames
+            );
+        }
+
+        let mut glyphs = HashMap::new();
+        for (frame, c) in definition.glyphs.chars().enumerate() {
+            let glyph = Glyph {
+                frame,
+                advance: definition.widths.get(&c).copied().unwrap_or(advance),
+            };
+            if glyphs.insert(c, glyph).is_some() {
+                bail!("glyph {:?} is defined twice", c);
+            }
+        }
+
+        let unknown = definition
+            .widths
+            .keys()
+            .chain(definition.kerning.iter().flat_map(|(a, b, _)| [a, b]))
+            .chain(definition.fallback.iter())
+            .find(|c| !glyphs.contains_key(c));
+        if let Some(c) = unknown {
+            bail!("glyph {:?} is not part of the font", c);
+        }
+
+        Ok(Self {
+            sprite: sprite.id,
+            glyph_size: (width, height),
+            glyphs,
+            kerning: definition
+                .kerning
+                .iter()
+                .map(|&(a, b, adjustment)| ((a, b), adjustment))
+                .collect(),
+            advance,
+            line_height: definition.line_height.unwrap_or(height as i32),
+            fallback: definition.fallback,
+        })
+    }
+
+    pub fn sprite(&self) -> SpriteId {
+        self.sprite
+    }
+
+    pub fn glyph_size(&self) -> (usize, usize) {
+        self.glyph_size
+    }
+
+    /// Glyph shown for `c`, trying the uppercase letter and the fallback glyph if the font
+    /// doesn't have it
+    fn glyph(&self, c: char) -> Option<(char, Glyph)> {
+        [Some(c), c.to_uppercase().next(), self.fallback]
+            .into_iter()
+            .flatten()
+            .find_map(|c| self.glyphs.get(&c).map(|glyph| (c, *glyph)))
+    }
+
+    /// Glyphs of a single line with their x offsets, starting at 0
+    fn layout_line(&self, line: &str) -> (Vec<(usize, i32)>, i32) {
+        let mut glyphs = Vec::with_capacity(line.len());
+        let mut x = 0;
+        let mut previous = None;
+
+        for c in line.chars() {
+            let Some((c, glyph)) = self.glyph(c) else {
+                x += self.advance;
+                previous = None;
+                continue;
+            };
+
+            if let Some(previous) = previous {
+                x += self.kerning.get(&(previous, c)).copied().unwrap_or(0);
+            }
+            glyphs.push((glyph.frame, x));
+            x += glyph.advance;
+            previous = Some(c);
+        }
+
+        (glyphs, x)
+    }
+
+    /// Width and height of a (multi-line) text in pixels
+    pub fn measure(&self, text: &str) -> (i32, i32) {
+        let lines = text.split('\n');
+        let line_count = lines.clone().count() as i32;
+        let width = lines
+            .map(|line| self.layout_line(line).1)
+            .max()
+            .unwrap_or(0);
+
+        (
+            width,
+            (line_count - 1) * self.line_height + self.glyph_size.1 as i32,
+        )
+    }
+
+    /// Glyphs of a (multi-line) text, the position of the text is at the top of the first line,
+    /// left of, centered on or right of every line depending on `alignment`
+    pub fn layout(&self, text: &str, alignment: Alignment) -> Vec<PlacedGlyph> {
+        let mut placed = Vec::with_capacity(text.len());
+
+        for (line_idx, line) in text.split('\n').enumerate() {
+            let (glyphs, width) = self.layout_line(line);
+            let start = match alignment {
+                Alignment::Left => 0,
+                Alignment::Center => -width / 2,
+                Alignment::Right => -width,
+            };
+
+            placed.extend(glyphs.into_iter().map(|(frame, x)| PlacedGlyph {
+                frame,
+                x: start + x,
+                y: line_idx as i32 * self.line_height,
+            }));
+        }
+
+        placed
+    }
+}
+
+/// All fonts, by name
+#[derive(Default)]
+pub struct FontLibrary {
+    fonts: HashMap<String, Arc<BitmapFont>>,
+}
+
+impl FontLibrary {
+    /// Load fonts from a RON file mapping names to fonts, glyph sprites are looked up in `catalog`
+    pub fn load<P: AsRef<Path>>(file: P, catalog: &AssetCatalog) -> Result<Self> {
+        let file = file.as_ref();
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read font file {}", file.display()))?;
+
+        Self::parse(&content, catalog)
+            .with_context(|| format!("Invalid font file {}", file.display()))
+    }
+
+    pub fn parse(content: &str, catalog: &AssetCatalog) -> Result<Self> {
+        let definitions: HashMap<String, FontDefinition> = ron::from_str(content)?;
+
+        let fonts = definitions
+            .into_iter()
+            .map(|(name, definition)| {
+                let font = BitmapFont::from_definition(&definition, catalog)
+                    .with_context(|| format!("invalid font \"{}\"", name))?;
+                Ok((name, Arc::new(font)))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { fonts })
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<BitmapFont>> {
+        self.fonts.get(name).cloned()
+    }
+}
@@ -0,0 +1,465 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::{channel, Receiver},
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context, Result};
+use log::warn;
+use sdl2::{
+    mixer::Music,
+    render::{Canvas, TextureCreator},
+};
+use specs::{Dispatcher, DispatcherBuilder, Join, World, WorldExt};
+
+use crate::{
+    assets::{Assets, HeadlessAssetLoader, SdlAssetLoader},
+    component::{self, sprite::SpriteComponent},
+    entity::{enemy::EnemyLibrary, player::Player},
+    errors::SdlError,
+    font::FontLibrary,
+    hud::{Hud, HudLayout},
+    level::{self, Level, LevelContext, LevelDirector},
+    particle::EmitterLibrary,
+    path::PathLibrary,
+    pattern::PatternLibrary,
+    resource::{
+        background::Background,
+        collision::Collisions,
+        debug::DebugOverlay,
+        display::Display,
+        game_state::GameState,
+        player_input::PlayerInput,
+        playfield::Playfield,
+        screenshot::{Resolution, Screenshots},
+        sound::{AudioInterface, AudioRequest},
+        spatial_hash::SpatialHash,
+        timing::Timing,
+    },
+    sound::SoundLibrary,
+    sprite::SpriteMasks,
+    state_machine::StateMachineLibrary,
+    system::{
+        animation_state::AnimationStateSystem,
+        background_scroll::BackgroundScrollSystem,
+        bounds::BoundsSystem,
+        bullet_emitter::BulletEmitterSystem,
+        bullet_physics::BulletPhysicsSystem,
+        collision::CollisionSystem,
+        damage::DamageSystem,
+        death::DeathSystem,
+        debug_stats::DebugStatsSystem,
+        level_director::LevelDirectorSystem,
+        lifetime::LifetimeSystem,
+        particle::ParticleSystem,
+        path_follow::PathFollowSystem,
+        player_movement::PlayerMovementSystem,
+        player_weapon::PlayerWeaponSystem,
+        render::{RenderSystem, Screen},
+        spatial_hash::SpatialHashSystem,
+        sprite_animation::SpriteAnimationSystem,
+        track_position::PositionTrackSystem,
+    },
+    FRAME_RATE_GAME, GAME_HEIGHT, GAME_WIDTH,
+};
+
+/// Movement paths of all enemies
+const PATH_FILE: &str = "data/paths.ron";
+
+/// Bullet patterns of all enemy weapons
+const PATTERN_FILE: &str = "data/patterns.ron";
+
+/// Types of all enemies
+const ENEMY_FILE: &str = "data/enemies.ron";
+
+/// Particle emitters of explosions, engines, etc.
+const PARTICLE_FILE: &str = "data/particles.ron";
+
+/// Animation state machines of the player and enemies
+const ANIMATION_FILE: &str = "data/animations.ron";
+
+/// Bitmap fonts for the HUD and texts in the game world
+const FONT_FILE: &str = "data/fonts.ron";
+
+/// Layout of the HUD
+const HUD_FILE: &str = "data/hud.ron";
+
+/// Stage played when no other level is chosen
+const DEFAULT_LEVEL: &str = "data/levels/stage1.ron";
+
+/// Data files level files refer to
+struct GameData {
+    enemies: EnemyLibrary,
+    paths: PathLibrary,
+    patterns: PatternLibrary,
+    particles: EmitterLibrary,
+    animations: StateMachineLibrary,
+}
+
+impl GameData {
+    fn load(assets: &Assets) -> Result<Self> {
+        Ok(Self {
+            enemies: EnemyLibrary::load(ENEMY_FILE)?,
+            paths: PathLibrary::load(PATH_FILE)?,
+            patterns: PatternLibrary::load(PATTERN_FILE)?,
+            particles: EmitterLibrary::load(PARTICLE_FILE, &assets.catalog)?,
+            animations: StateMachineLibrary::load(ANIMATION_FILE)?,
+        })
+    }
+
+    fn level_context<'a>(&'a self, assets: &'a Assets) -> LevelContext<'a> {
+        LevelContext {
+            catalog: &assets.catalog,
+            enemies: &self.enemies,
+            paths: &self.paths,
+            patterns: &self.patterns,
+            particles: &self.particles,
+            animations: &self.animations,
+        }
+    }
+}
+
+/// Check a level file and everything it refers to, without running it
+pub fn validate_level<P: AsRef<Path>>(file: P) -> Result<()> {
+    let assets = Assets::load(&mut HeadlessAssetLoader::new())?;
+    let data = GameData::load(&assets)?;
+
+    level::validate_level(file, &data.level_context(&assets))
+}
+
+/// Sets up the world, its resources and the dispatchers of a game
+pub struct GameBuilder {
+    spawn_player: bool,
+    level: Option<PathBuf>,
+    playfield: (f32, f32),
+    display: Display,
+}
+
+impl Default for GameBuilder {
+    fn default() -> Self {
+        Self {
+            spawn_player: true,
+            level: Some(PathBuf::from(DEFAULT_LEVEL)),
+            playfield: (GAME_WIDTH as f32, GAME_HEIGHT as f32),
+            display: Display::default(),
+        }
+    }
+}
+
+impl GameBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start with an empty world instead of spawning the player
+    pub fn without_player(mut self) -> Self {
+        self.spawn_player = false;
+        self
+    }
+
+    /// Play the given level file instead of the first stage
+    pub fn with_level<P: Into<PathBuf>>(mut self, file: P) -> Self {
+        self.level = Some(file.into());
+        self
+    }
+
+    /// Start without any level, so no enemies are spawned
+    pub fn without_level(mut self) -> Self {
+        self.level = None;
+        self
+    }
+
+    /// Size of the area the game is played in (defaults to the game resolution)
+    pub fn with_playfield(mut self, width: f32, height: f32) -> Self {
+        self.playfield = (width, height);
+        self
+    }
+
+    /// Display settings the window and canvas passed to `build` were created with
+    pub fn with_display(mut self, display: Display) -> Self {
+        self.display = display;
+        self
+    }
+
+    /// Build a game without renderer and audio, assets are not loaded from disk
+    pub fn build_headless(self) -> Result<Game<'static>> {
+        let mut asset_loader = HeadlessAssetLoader::new();
+        let assets = Assets::load(&mut asset_loader)?;
+        let (sprite_manager, sound_library) = asset_loader.into_parts();
+        let fonts = FontLibrary::load(FONT_FILE, &assets.catalog)?;
+
+        self.build_game(
+            &assets,
+            fonts,
+            sprite_manager.masks(),
+            sound_library,
+            None,
+            false,
+        )
+    }
+
+    /// Build a game rendering to `canvas`, loading textures via `texture_creator`
+    ///
+    /// Sounds are loaded as well, so the SDL mixer needs to be opened beforehand.
+    pub fn build<'t, T, C>(
+        self,
+        canvas: Canvas<T>,
+        texture_creator: &'t TextureCreator<C>,
+    ) -> Result<Game<'t>>
+    where
+        T: Screen + 't,
+    {
+        if !canvas.render_target_supported() {
+            bail!("Renderer doesn't support render targets");
+        }
+        let target = texture_creator
+            .create_texture_target(None, GAME_WIDTH, GAME_HEIGHT)
+            .context("Failed to create render target")?;
+
+        let mut asset_loader = SdlAssetLoader::new(texture_creator);
+        let assets = Assets::load(&mut asset_loader)?;
+        let (sprite_manager, sound_library) = asset_loader.into_parts();
+        let sprite_masks = sprite_manager.masks();
+        let fonts = FontLibrary::load(FONT_FILE, &assets.catalog)?;
+        let hud = Hud::new(HudLayout::load(HUD_FILE)?, &fonts)?;
+
+        let dispatcher_render = DispatcherBuilder::new()
+            .with_thread_local(DebugStatsSystem)
+            .with_thread_local(RenderSystem::new(
+                canvas,
+                target,
+                sprite_manager,
+                hud,
+                self.display.clone(),
+            ))
+            .build();
+
+        self.build_game(
+            &assets,
+            fonts,
+            sprite_masks,
+            sound_library,
+            Some(dispatcher_render),
+            true,
+        )
+    }
+
+    fn build_game<'t>(
+        self,
+        assets: &Assets,
+        fonts: FontLibrary,
+        sprite_masks: SpriteMasks,
+        sound_library: SoundLibrary,
+        dispatcher_render: Option<Dispatcher<'static, 't>>,
+        audio_enabled: bool,
+    ) -> Result<Game<'t>> {
+        let (audio_sender, audio_receiver) = channel::<AudioRequest>();
+
+        let data = GameData::load(assets)?;
+        let (director, background) = match &self.level {
+            Some(file) => {
+                let level = Level::load(file)?;
+                let context = data.level_context(assets);
+                (
+                    LevelDirector::new(&level, &context)?,
+                    level.background(&context)?,
+                )
+            }
+            None => (LevelDirector::default(), Background::default()),
+        };
+
+        let mut world = World::new();
+        world.insert(PlayerInput::default());
+        world.insert(Timing::default());
+        world.insert(Playfield::new(self.playfield.0, self.playfield.1));
+        world.insert(Collisions::default());
+        world.insert(GameState::default());
+        world.insert(self.display.clone());
+        world.insert(Screenshots::default());
+        world.insert(DebugOverlay::default());
+        world.insert(fonts);
+        world.insert(SpatialHash::default());
+        world.insert(sprite_masks);
+        world.insert(AudioInterface::new(audio_sender));
+        component::register_all(&mut world);
+
+        {
+            let mut timing = world.write_resource::<Timing>();
+            timing.delta_time = physics_tick_duration();
+        }
+
+        if self.spawn_player {
+            Player::create_player(
+                &mut world,
+                assets,
+                &data.animations,
+                &data.particles,
+                self.playfield.0 / 2.0,
+                self.playfield.1 - 100.0,
+            );
+        }
+
+        world.insert(director);
+        world.insert(background);
+        world.insert(data.particles);
+
+        let dispatcher_game = DispatcherBuilder::new()
+            .with(PlayerMovementSystem, "player_movement", &[])
+            .with(BulletPhysicsSystem, "bullet_physics", &[])
+            .with(LevelDirectorSystem, "level_director", &[])
+            .with(
+                BackgroundScrollSystem,
+                "background_scroll",
+                &["level_director"],
+            )
+            .with(PathFollowSystem, "path_follow", &[])
+            .with(
+                BoundsSystem,
+                "bounds",
+                &["player_movement", "bullet_physics", "path_follow"],
+            )
+            .with(PlayerWeaponSystem, "player_weapon", &["bounds"])
+            .with(PositionTrackSystem, "position_track", &["bounds"])
+            .with(
+                SpatialHashSystem,
+                "spatial_hash",
+                &["bounds", "position_track"],
+            )
+            .with(CollisionSystem, "collision", &["spatial_hash"])
+            .with(BulletEmitterSystem, "bullet_emitter", &["spatial_hash"])
+            .with(DamageSystem, "damage", &["collision"])
+            .with(DeathSystem, "death", &["damage"])
+            .with(LifetimeSystem, "lifetime", &[])
+            .with(
+                AnimationStateSystem,
+                "animation_state",
+                &["player_movement", "player_weapon", "bullet_emitter"],
+            )
+            .with(
+                SpriteAnimationSystem,
+                "sprite_animation",
+                &["animation_state"],
+            )
+            .with(ParticleSystem, "particles", &["bounds", "position_track"])
+            .build();
+
+        Ok(Game {
+            world,
+            dispatcher_game,
+            dispatcher_render,
+            sound_library,
+            audio_receiver,
+            audio_enabled,
+            music: None,
+            ticks: 0,
+        })
+    }
+}
+
+/// A game world together with the dispatchers driving it
+pub struct Game<'t> {
+    world: World,
+    dispatcher_game: Dispatcher<'static, 'static>,
+    dispatcher_render: Option<Dispatcher<'static, 't>>,
+    sound_library: SoundLibrary,
+    audio_receiver: Receiver<AudioRequest>,
+    audio_enabled: bool,
+    /// Currently playing music, stops when dropped
+    music: Option<Music<'static>>,
+    ticks: u64,
+}
+
+impl<'t> Game<'t> {
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+
+    /// Number of physics ticks run so far
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    /// Run a single physics tick with the given input
+    pub fn tick(&mut self, input: PlayerInput, physics_tick: Instant) {
+        {
+            let mut timing = self.world.write_resource::<Timing>();
+            timing.physics_tick = physics_tick;
+
+            *self.world.write_resource::<PlayerInput>() = input;
+
+            // Rendering interpolates from the state at the end of the previous tick
+            for sprite in (&mut self.world.write_storage::<SpriteComponent>()).join() {
+                sprite.store_previous();
+            }
+        }
+
+        self.dispatcher_game.dispatch(&self.world);
+        self.world.maintain();
+
+        self.ticks += 1;
+    }
+
+    /// Render the current state, does nothing for headless games
+    pub fn render(&mut self) {
+        if let Some(dispatcher_render) = &mut self.dispatcher_render {
+            dispatcher_render.dispatch(&self.world);
+        }
+    }
+
+    /// Render the current state and write it to a PNG file
+    pub fn screenshot<P: Into<PathBuf>>(&mut self, path: P, resolution: Resolution) -> Result<()> {
+        if self.dispatcher_render.is_none() {
+            bail!("Headless games can't take screenshots");
+        }
+
+        let path = path.into();
+        self.world
+            .write_resource::<Screenshots>()
+            .request_to(path.clone(), resolution);
+        self.render();
+
+        self.world
+            .write_resource::<Screenshots>()
+            .take_finished()
+            .into_iter()
+            .find(|(finished, _)| *finished == path)
+            .map_or_else(
+                || bail!("Screenshot {} was not captured", path.display()),
+                |(_, result)| result,
+            )
+    }
+
+    /// Play all sounds triggered since the last call (headless games just discard them)
+    pub fn play_sounds(&mut self) -> Result<()> {
+        for request in self.audio_receiver.try_iter() {
+            match request {
+                AudioRequest::Sound(sound) => {
+                    if let Some(chunk) = self.sound_library.get(sound) {
+                        // All channels busy: skipping a sound beats ending the game
+                        if let Err(e) = sdl2::mixer::Channel::all().play(chunk, 0) {
+                            warn!(target: "Game", "Failed to play sound: {}", e);
+                        }
+                    }
+                }
+                AudioRequest::Music(file) if self.audio_enabled => {
+                    let music = Music::from_file(&file)
+                        .map_err(SdlError::SoundLoadError)
+                        .with_context(|| format!("Failed to load music {}", file))?;
+                    music.play(-1).map_err(SdlError::AudioPlayError)?;
+                    self.music = Some(music);
+                }
+                AudioRequest::Music(_) => (),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn physics_tick_duration() -> Duration {
+    Duration::from_nanos(1_000_000_000u64 / (FRAME_RATE_GAME as u64))
+}
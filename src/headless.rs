@@ -0,0 +1,146 @@
+use std::{path::Path, time::Instant};
+
+use anyhow::{bail, Context, Result};
+use specs::{World, WorldExt};
+
+use crate::{
+    resource::{player_input::PlayerInput, timing::Timing},
+    Game, GameBuilder,
+};
+
+/// Provides the player input for each physics tick
+pub trait InputSource {
+    fn input_at(&self, tick: u64) -> PlayerInput;
+}
+
+/// Player input over time, as a list of (tick, input) changes
+///
+/// Text format: one change per line, `<tick> [left] [right] [up] [down] [shoot]`.
+/// The input stays active until the next change; empty lines and lines starting with `#` are ignored.
+#[derive(Debug, Default)]
+pub struct InputScript {
+    changes: Vec<(u64, PlayerInput)>,
+}
+
+impl InputScript {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let script = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read input script {}", path.display()))?;
+        Self::parse(&script).with_context(|| format!("Invalid input script {}", path.display()))
+    }
+
+    pub fn parse(script: &str) -> Result<Self> {
+        let mut changes: Vec<(u64, PlayerInput)> = Vec::new();
+
+        for (line_idx, line) in script.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut tokens = line.split_whitespace();
+            let tick = tokens
+                .next()
+                .expect("line should not be empty")
+                .parse::<u64>()
+                .with_context(|| format!("line {}: expected tick number", line_idx + 1))?;
+
+            if let Some((previous_tick, _)) = changes.last() {
+                if tick <= *previous_tick {
+                    bail!(
+                        "line {}: tick {} is not after previous tick {}",
+                        line_idx + 1,
+                        tick,
+                        previous_tick
+                    );
+                }
+            }
+
+            let mut input = PlayerInput::default();
+            for token in tokens {
+                match token {
+                    "left" => input.left = true,
+                    "right" => input.right = true,
+                    "up" => input.up = true,
+                    "down" => input.down = true,
+                    "shoot" => input.shoot_air = true,
+                    _ => bail!("line {}: unknown input '{}'", line_idx + 1, token),
+                }
+            }
+
+            changes.push((tick, input));
+        }
+
+        Ok(Self { changes })
+    }
+}
+
+impl InputSource for InputScript {
+    /// Input active at the given tick
+    fn input_at(&self, tick: u64) -> PlayerInput {
+        let idx = self.changes.partition_point(|(t, _)| *t <= tick);
+        if idx == 0 {
+            PlayerInput::default()
+        } else {
+            self.changes[idx - 1].1
+        }
+    }
+}
+
+/// Runs the game systems at a fixed tick rate, without window, renderer or audio
+pub struct Simulation {
+    game: Game<'static>,
+    start: Instant,
+}
+
+impl Simulation {
+    pub fn new() -> Result<Self> {
+        Ok(Self::from_game(GameBuilder::new().build_headless()?))
+    }
+
+    pub fn from_game(game: Game<'static>) -> Self {
+        Self {
+            game,
+            start: Instant::now(),
+        }
+    }
+
+    /// Advance the simulation by a single physics tick, fails if a requested sound or music
+    /// can't be played
+    pub fn step(&mut self, input: PlayerInput) -> Result<()> {
+        let delta_time = self.game.world().read_resource::<Timing>().delta_time;
+        let physics_tick = self.start + delta_time * (self.game.ticks() + 1) as u32;
+
+        self.game.tick(input, physics_tick);
+
+        // Headless games just discard the sounds
+        self.game.play_sounds()
+    }
+
+    /// Advance the simulation by `ticks` physics ticks, feeding input from `input`
+    pub fn run<I: InputSource>(&mut self, ticks: u64, input: &I) -> Result<()> {
+        for _ in 0..ticks {
+            let input = input.input_at(self.game.ticks());
+            self.step(input)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn game(&self) -> &Game<'static> {
+        &self.game
+    }
+
+    pub fn game_mut(&mut self) -> &mut Game<'static> {
+        &mut self.game
+    }
+
+    pub fn world(&self) -> &World {
+        self.game.world()
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.game.ticks()
+    }
+}
@@ -0,0 +1,138 @@
+use std::{fs, path::Path, sync::Arc};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+use crate::{
+    font::{BitmapFont, FontLibrary},
+    resource::game_state::GameState,
+};
+
+/// Corner or edge of the window a HUD element is placed relative to
+#[derive(Debug, Copy, Clone, Deserialize)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+/// Game state shown by a HUD element
+#[derive(Debug, Copy, Clone, Deserialize)]
+pub enum HudValue {
+    Score,
+    Lives,
+    Bombs,
+    WeaponLevel,
+}
+
+/// Single line of text as written in the HUD file
+#[derive(Debug, Clone, Deserialize)]
+pub struct HudElement {
+    pub value: HudValue,
+    /// Text in front of the value
+    #[serde(default)]
+    pub label: String,
+    pub anchor: Anchor,
+    /// Offset from the anchor in window pixels, positive values point into the window
+    #[serde(default)]
+    pub offset: (i32, i32),
+    #[serde(default = "default_color")]
+    pub color: (u8, u8, u8),
+    /// Minimum number of digits, padded with zeros
+    #[serde(default)]
+    pub digits: usize,
+}
+
+fn default_color() -> (u8, u8, u8) {
+    (255, 255, 255)
+}
+
+impl HudElement {
+    pub fn text(&self, state: &GameState) -> String {
+        let value = match self.value {
+            HudValue::Score => state.score,
+            HudValue::Lives => state.lives as u64,
+            HudValue::Bombs => state.bombs as u64,
+            HudValue::WeaponLevel => state.weapon_level as u64,
+        };
+
+        format!("{}{:0digits$}", self.label, value, digits = self.digits)
+    }
+
+    /// Top left corner of the element in window pixels, for a text of the given size
+    pub fn position(&self, window_size: (u32, u32), text_size: (u32, u32)) -> (i32, i32) {
+        let (window_width, window_height) = (window_size.0 as i32, window_size.1 as i32);
+        let (text_width, text_height) = (text_size.0 as i32, text_size.1 as i32);
+        let (dx, dy) = self.offset;
+
+        let x = match self.anchor {
+            Anchor::TopLeft | Anchor::BottomLeft => dx,
+            Anchor::Top | Anchor::Bottom => (window_width - text_width) / 2 + dx,
+            Anchor::TopRight | Anchor::BottomRight => window_width - text_width - dx,
+        };
+        let y = match self.anchor {
+            Anchor::TopLeft | Anchor::Top | Anchor::TopRight => dy,
+            Anchor::BottomLeft | Anchor::Bottom | Anchor::BottomRight => {
+                window_height - text_height - dy
+            }
+        };
+
+        (x, y)
+    }
+}
+
+/// Layout of the HUD as written in the HUD file
+#[derive(Debug, Clone, Deserialize)]
+pub struct HudLayout {
+    /// Name of the font in the font file
+    pub font: String,
+    /// Integer factor the text is magnified by
+    #[serde(default = "default_scale")]
+    pub scale: u32,
+    pub elements: Vec<HudElement>,
+}
+
+fn default_scale() -> u32 {
+    1
+}
+
+impl HudLayout {
+    pub fn load<P: AsRef<Path>>(file: P) -> Result<Self> {
+        let file = file.as_ref();
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read HUD file {}", file.display()))?;
+
+        Self::parse(&content).with_context(|| format!("Invalid HUD file {}", file.display()))
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        let layout: Self = ron::from_str(content)?;
+        if layout.scale == 0 {
+            bail!("scale must be at least 1");
+        }
+
+        Ok(layout)
+    }
+}
+
+/// HUD drawn in window pixels on top of the game world
+pub struct Hud {
+    pub font: Arc<BitmapFont>,
+    pub scale: u32,
+    pub elements: Vec<HudElement>,
+}
+
+impl Hud {
+    pub fn new(layout: HudLayout, fonts: &FontLibrary) -> Result<Self> {
+        Ok(Self {
+            font: fonts
+                .get(&layout.font)
+                .with_context(|| format!("unknown HUD font \"{}\"", layout.font))?,
+            scale: layout.scale,
+            elements: layout.elements,
+        })
+    }
+}
@@ -0,0 +1,906 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+    path::Path as FilePath,
+    sync::Arc,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+
+use crate::{
+    assets::{AssetCatalog, SpriteEntry},
+    entity::enemy::{EnemyLibrary, EnemyType, EnemyWeapon},
+    particle::EmitterLibrary,
+    path::{Path, PathLibrary},
+    pattern::PatternLibrary,
+    resource::background::{Background, BackgroundPlane},
+    sound::SoundId,
+    sprite::SpriteDescription,
+    state_machine::StateMachineLibrary,
+    tiled,
+};
+
+/// When a timeline event happens
+#[derive(Debug, Copy, Clone, Deserialize)]
+pub enum Trigger {
+    /// Time since the stage started in s
+    Time(f32),
+    /// Distance scrolled since the stage started in pixels
+    Distance(f32),
+}
+
+/// Timeline event as written in the level file
+#[derive(Debug, Clone, Deserialize)]
+pub enum LevelEvent {
+    /// Spawn an enemy of the given type, optionally following a path starting at `position`
+    Spawn {
+        enemy: String,
+        position: (f32, f32),
+        #[serde(default)]
+        path: Option<String>,
+    },
+    /// Start looping the music in the given file
+    Music { file: String },
+    /// Warn the player about the approaching boss for `duration` s
+    BossWarning { duration: f32 },
+    /// The stage is over, no further events are processed
+    EndStage,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TimelineEntry {
+    pub trigger: Trigger,
+    pub event: LevelEvent,
+}
+
+/// Background plane as written in the level file
+#[derive(Debug, Clone, Deserialize)]
+pub struct PlaneDefinition {
+    /// Sprite sheet the tiles are taken from, one frame per tile
+    pub sprite: String,
+    /// Factor applied to the scroll speed
+    #[serde(default = "default_parallax")]
+    pub parallax: f32,
+    #[serde(default = "default_repeat")]
+    pub repeat: bool,
+    /// Frame index of every tile, top row first, -1 for no tile
+    pub tiles: Vec<Vec<i32>>,
+    /// Frame slicing the tiles were made for, checked against the sprite (set by map imports)
+    #[serde(skip)]
+    pub slicing: Option<SpriteDescription>,
+}
+
+fn default_parallax() -> f32 {
+    1.0
+}
+
+fn default_repeat() -> bool {
+    true
+}
+
+/// Background as written in the level file
+#[derive(Debug, Clone, Deserialize)]
+pub struct BackgroundDefinition {
+    /// Color shown where no plane has a tile
+    pub color: (u8, u8, u8),
+    /// Planes in render order, the first one is the furthest away
+    #[serde(default)]
+    pub planes: Vec<PlaneDefinition>,
+}
+
+/// Stage description as written in the level file
+#[derive(Debug, Clone, Deserialize)]
+pub struct Level {
+    pub name: String,
+    /// Scroll speed in pixels / s, used by distance triggers and the background
+    #[serde(default)]
+    pub scroll_speed: f32,
+    #[serde(default)]
+    pub background: Option<BackgroundDefinition>,
+    pub events: Vec<TimelineEntry>,
+    /// Where events and planes are written, to report errors by line (level files only)
+    #[serde(skip)]
+    pub source: Option<SourceLines>,
+}
+
+/// Lines in the level file the timeline entries and background planes start on
+#[derive(Debug, Clone, Default)]
+pub struct SourceLines {
+    pub file: String,
+    pub events: Vec<usize>,
+    pub planes: Vec<usize>,
+}
+
+impl SourceLines {
+    fn scan(file: &FilePath, content: &str) -> Self {
+        Self {
+            file: file.display().to_string(),
+            events: element_lines(content, &["events"]),
+            planes: element_lines(content, &["background", "planes"]),
+        }
+    }
+
+    /// `file:line: ` prefix for an error in the element `idx` of `lines`
+    fn location(&self, lines: &[usize], idx: usize) -> String {
+        lines
+            .get(idx)
+            .map(|line| format!("{}:{}: ", self.file, line))
+            .unwrap_or_default()
+    }
+}
+
+/// Lines (starting at 1) of the elements of the list reached by the struct fields `path`
+///
+/// A lenient scan of the RON text, only run on files that deserialized without errors.
+fn element_lines(content: &str, path: &[&str]) -> Vec<usize> {
+    struct Frame {
+        /// Field of the parent struct this bracket is the value of
+        field: Option<String>,
+        /// Field currently being read, for structs
+        key: Option<String>,
+        is_target: bool,
+        expect_element: bool,
+    }
+
+    let mut lines = Vec::new();
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut ident = String::new();
+    let mut last_ident: Option<String> = None;
+    let mut line = 1;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let comment = c == '/' && matches!(chars.peek(), Some('/' | '*'));
+        if !c.is_whitespace() && !comment {
+            if let Some(frame) = stack.last_mut().filter(|frame| frame.expect_element) {
+                if c != ']' {
+                    lines.push(line);
+                }
+                frame.expect_element = false;
+            }
+        }
+
+        if c.is_alphanumeric() || c == '_' {
+            ident.push(c);
+            continue;
+        }
+        let raw_string = ident == "r" && matches!(c, '"' | '#');
+        if !ident.is_empty() {
+            last_ident = Some(std::mem::take(&mut ident));
+        }
+        if !c.is_whitespace() && !comment && c != ':' {
+            // Only an identifier right before a colon is a field name
+            last_ident = None;
+        }
+
+        match c {
+            // r"...", r#"..."#, etc. without escapes
+            _ if raw_string => {
+                let mut hashes = 0;
+                let mut c = c;
+                while c == '#' {
+                    hashes += 1;
+                    c = chars.next().unwrap_or('"');
+                }
+                let mut closing = None;
+                for c in chars.by_ref() {
+                    match (c, closing) {
+                        ('\n', _) => {
+                            line += 1;
+                            closing = None;
+                        }
+                        ('"', _) => closing = Some(0),
+                        ('#', Some(found)) => closing = Some(found + 1),
+                        _ => closing = None,
+                    }
+                    if closing == Some(hashes) {
+                        break;
+                    }
+                }
+            }
+            '\n' => line += 1,
+            // Char literal, may be a quote or bracket
+            '\'' => {
+                if chars.next() == Some('\\') {
+                    chars.next();
+                }
+                for c in chars.by_ref() {
+                    if c == '\'' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        line += 1;
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            '"' => {
+                let mut escaped = false;
+                for c in chars.by_ref() {
+                    match c {
+                        '\n' => line += 1,
+                        '"' if !escaped => break,
+                        _ => (),
+                    }
+                    escaped = c == '\\' && !escaped;
+                }
+            }
+            ':' => {
+                if let Some(frame) = stack.last_mut() {
+                    frame.key = last_ident.take();
+                }
+            }
+            ',' => {
+                if let Some(frame) = stack.last_mut() {
+                    frame.key = None;
+                    frame.expect_element = frame.is_target;
+                }
+            }
+            '(' | '[' | '{' => {
+                let field = stack.last().and_then(|frame| frame.key.clone());
+                let fields: Vec<_> = stack
+                    .iter()
+                    .filter_map(|frame| frame.field.as_deref())
+                    .chain(field.as_deref())
+                    .collect();
+                let is_target = c == '[' && fields == path;
+                stack.push(Frame {
+                    field,
+                    key: None,
+                    is_target,
+                    expect_element: is_target,
+                });
+            }
+            ')' | ']' | '}' => {
+                stack.pop();
+            }
+            _ => (),
+        }
+    }
+
+    lines
+}
+
+impl Level {
+    /// Load a level file, syntax errors are reported with line and column, unknown names with the
+    /// line of their event or plane
+    ///
+    /// Maps made with the Tiled editor are imported from .tmx and .tmj/.json files. Their tilesets
+    /// only name a sprite of the catalog, which has to be sliced like the tileset (a single row of
+    /// tiles); the tileset images are not loaded.
+    pub fn load<P: AsRef<FilePath>>(file: P) -> Result<Self> {
+        let file = file.as_ref();
+        match file.extension().and_then(|extension| extension.to_str()) {
+            Some("tmx") => return tiled::load_tmx(file),
+            Some("tmj" | "json") => return tiled::load_json(file),
+            _ => (),
+        }
+
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read level file {}", file.display()))?;
+
+        let mut level: Level = ron::from_str(&content).map_err(|e| {
+            anyhow!(
+                "{}:{}:{}: {}",
+                file.display(),
+                e.position.line,
+                e.position.col,
+                e.code
+            )
+        })?;
+        level.source = Some(SourceLines::scan(file, &content));
+
+        Ok(level)
+    }
+
+    /// Background of the level with all tiles resolved, the default background if there is none
+    pub fn background(&self, context: &LevelContext) -> Result<Background> {
+        let mut resolver = Resolver::new(context);
+        let background = resolver.resolve_background(self);
+        resolver
+            .finish()
+            .with_context(|| format!("Invalid background of level \"{}\"", self.name))?;
+
+        Ok(background)
+    }
+}
+
+/// Everything level files can refer to
+pub struct LevelContext<'a> {
+    pub catalog: &'a AssetCatalog,
+    pub enemies: &'a EnemyLibrary,
+    pub paths: &'a PathLibrary,
+    pub patterns: &'a PatternLibrary,
+    pub particles: &'a EmitterLibrary,
+    pub animations: &'a StateMachineLibrary,
+}
+
+/// Timeline event with all references resolved
+#[derive(Debug, Clone)]
+pub enum DirectorEvent {
+    Spawn {
+        enemy: Arc<EnemyType>,
+        position: (f32, f32),
+        path: Option<Arc<Path>>,
+    },
+    Music(String),
+    BossWarning(f32),
+    EndStage,
+}
+
+/// Resolves names used in a level, collecting all problems instead of stopping at the first
+struct Resolver<'a> {
+    context: &'a LevelContext<'a>,
+    enemy_types: HashMap<String, Arc<EnemyType>>,
+    /// Asset files the level depends on
+    files: BTreeSet<String>,
+    errors: Vec<String>,
+}
+
+impl<'a> Resolver<'a> {
+    fn new(context: &'a LevelContext<'a>) -> Self {
+        Self {
+            context,
+            enemy_types: HashMap::new(),
+            files: BTreeSet::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    fn resolve(&mut self, level: &Level) -> Vec<(Trigger, DirectorEvent)> {
+        level
+            .events
+            .iter()
+            .enumerate()
+            .filter_map(|(i, entry)| {
+                let event = self.resolve_event(&entry.event).map_err(|e| {
+                    let location = level
+                        .source
+                        .as_ref()
+                        .map(|source| source.location(&source.events, i))
+                        .unwrap_or_default();
+                    self.errors.push(format!(
+                        "{}event {} ({:?}): {:#}",
+                        location, i, entry.trigger, e
+                    ))
+                });
+                event.ok().map(|event| (entry.trigger, event))
+            })
+            .collect()
+    }
+
+    fn resolve_background(&mut self, level: &Level) -> Background {
+        let Some(definition) = &level.background else {
+            return Background::default();
+        };
+
+        let planes = definition
+            .planes
+            .iter()
+            .enumerate()
+            .filter_map(|(i, plane)| {
+                let plane = self.resolve_plane(plane).map_err(|e| {
+                    let location = level
+                        .source
+                        .as_ref()
+                        .map(|source| source.location(&source.planes, i))
+                        .unwrap_or_default();
+                    self.errors
+                        .push(format!("{}background plane {}: {:#}", location, i, e))
+                });
+                plane.ok()
+            })
+            .collect();
+
+        Background {
+            color: definition.color,
+            planes,
+        }
+    }
+
+    fn resolve_plane(&mut self, plane: &PlaneDefinition) -> Result<BackgroundPlane> {
+        let sprite = self.sprite(&plane.sprite)?;
+        let frames = sprite.description.number_of_frames;
+        if let Some(slicing) = plane.slicing {
+            if slicing != sprite.description {
+                bail!(
+                    "tiles are sliced as {:?}, but sprite \"{}\" as {:?}",
+                    slicing,
+                    plane.sprite,
+                    sprite.description
+                );
+            }
+        }
+
+        if !plane.parallax.is_finite() || plane.parallax < 0.0 {
+            bail!("parallax must not be negative");
+        }
+        let width = match plane.tiles.first() {
+            Some(row) if !row.is_empty() => row.len(),
+            _ => bail!("plane has no tiles"),
+        };
+
+        let tiles = plane
+            .tiles
+            .iter()
+            .enumerate()
+            .map(|(y, row)| {
+                if row.len() != width {
+                    bail!("row {} has {} tiles instead of {}", y, row.len(), width);
+                }
+                row.iter()
+                    .map(|&tile| match tile {
+                        -1 => Ok(None),
+                        tile if tile >= 0 && (tile as usize) < frames => Ok(Some(tile as usize)),
+                        tile => bail!(
+                            "tile {} in row {} is not a frame of sprite \"{}\"",
+                            tile,
+                            y,
+                            plane.sprite
+                        ),
+                    })
+                    .collect()
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(BackgroundPlane::new(
+            sprite.id,
+            sprite.description.frame_dimensions,
+            tiles,
+            plane.parallax,
+            plane.repeat,
+        ))
+    }
+
+    fn resolve_event(&mut self, event: &LevelEvent) -> Result<DirectorEvent> {
+        Ok(match event {
+            LevelEvent::Spawn {
+                enemy,
+                position,
+                path,
+            } => {
+                let path = path
+                    .as_ref()
+                    .map(|name| {
+                        self.context
+                            .paths
+                            .get(name)
+                            .with_context(|| format!("unknown path \"{}\"", name))
+                    })
+                    .transpose()?;
+
+                DirectorEvent::Spawn {
+                    enemy: self.enemy_type(enemy)?,
+                    position: *position,
+                    path,
+                }
+            }
+            LevelEvent::Music { file } => {
+                self.files.insert(file.clone());
+                DirectorEvent::Music(file.clone())
+            }
+            LevelEvent::BossWarning { duration } => DirectorEvent::BossWarning(*duration),
+            LevelEvent::EndStage => DirectorEvent::EndStage,
+        })
+    }
+
+    fn enemy_type(&mut self, name: &str) -> Result<Arc<EnemyType>> {
+        if let Some(enemy_type) = self.enemy_types.get(name) {
+            return Ok(enemy_type.clone());
+        }
+
+        let definition = self
+            .context
+            .enemies
+            .get(name)
+            .with_context(|| format!("unknown enemy \"{}\"", name))?;
+
+        let sprite = self.sprite(&definition.sprite)?;
+        let explosion_sprite = self.sprite(&definition.explosion_sprite)?;
+        let explosion_sound = self.sound(&definition.explosion_sound)?;
+        let explosion_particles = definition
+            .explosion_particles
+            .as_ref()
+            .map(|name| {
+                self.context
+                    .particles
+                    .get(name)
+                    .with_context(|| format!("unknown particle emitter \"{}\"", name))
+            })
+            .transpose()?;
+        let animation = definition
+            .animation
+            .as_ref()
+            .map(|name| -> Result<_> {
+                let animation = self
+                    .context
+                    .animations
+                    .get(name)
+                    .with_context(|| format!("unknown animation \"{}\"", name))?;
+                animation
+                    .validate_frames(sprite.description.number_of_frames)
+                    .with_context(|| format!("invalid animation \"{}\"", name))?;
+                Ok(animation)
+            })
+            .transpose()?;
+        let weapon = definition
+            .weapon
+            .as_ref()
+            .map(|weapon| -> Result<_> {
+                Ok(EnemyWeapon {
+                    pattern: self
+                        .context
+                        .patterns
+                        .get(&weapon.pattern)
+                        .with_context(|| format!("unknown pattern \"{}\"", weapon.pattern))?,
+                    bullet_sprite: self.sprite(&weapon.bullet_sprite)?.id,
+                })
+            })
+            .transpose()
+            .with_context(|| format!("invalid weapon of enemy \"{}\"", name))?;
+
+        let enemy_type = Arc::new(EnemyType {
+            sprite: sprite.id,
+            sprite_description: sprite.description,
+            health: definition.health,
+            score: definition.score,
+            weapon,
+            explosion_sprite: explosion_sprite.id,
+            explosion_sound,
+            explosion_particles,
+            animation,
+        });
+        self.enemy_types
+            .insert(name.to_string(), enemy_type.clone());
+
+        Ok(enemy_type)
+    }
+
+    fn sprite(&mut self, name: &str) -> Result<SpriteEntry> {
+        let entry = self
+            .context
+            .catalog
+            .sprite(name)
+            .with_context(|| format!("unknown sprite \"{}\"", name))?;
+        self.files.extend(entry.files.iter().cloned());
+        Ok(entry.clone())
+    }
+
+    fn sound(&mut self, name: &str) -> Result<SoundId> {
+        let entry = self
+            .context
+            .catalog
+            .sound(name)
+            .with_context(|| format!("unknown sound \"{}\"", name))?;
+        self.files.insert(entry.file.clone());
+        Ok(entry.id)
+    }
+
+    fn finish(self) -> Result<BTreeSet<String>> {
+        if !self.errors.is_empty() {
+            bail!(self.errors.join("\n"));
+        }
+        Ok(self.files)
+    }
+}
+
+/// Check that everything a level refers to exists, including the asset files on disk
+pub fn validate_level<P: AsRef<FilePath>>(file: P, context: &LevelContext) -> Result<()> {
+    let level = Level::load(&file)?;
+
+    let mut resolver = Resolver::new(context);
+    resolver.resolve_background(&level);
+    resolver.resolve(&level);
+    let files = resolver
+        .finish()
+        .with_context(|| format!("Invalid level file {}", file.as_ref().display()))?;
+
+    let missing: Vec<_> = files
+        .iter()
+        .filter(|file| !FilePath::new(file).exists())
+        .map(|file| format!("missing file {}", file))
+        .collect();
+    if !missing.is_empty() {
+        bail!(missing.join("\n"));
+    }
+
+    Ok(())
+}
+
+/// Runs the timeline of the current stage
+#[derive(Default)]
+pub struct LevelDirector {
+    name: String,
+    scroll_speed: f32,
+    /// Events not triggered yet, in file order
+    pending: Vec<(Trigger, DirectorEvent)>,
+    time: f32,
+    distance: f32,
+    /// Remaining time of the boss warning in s
+    boss_warning: f32,
+    stage_complete: bool,
+}
+
+impl LevelDirector {
+    pub fn new(level: &Level, context: &LevelContext) -> Result<Self> {
+        let mut resolver = Resolver::new(context);
+        let pending = resolver.resolve(level);
+        resolver
+            .finish()
+            .with_context(|| format!("Invalid level \"{}\"", level.name))?;
+
+        Ok(Self {
+            name: level.name.clone(),
+            scroll_speed: level.scroll_speed,
+            pending,
+            ..Default::default()
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Time since the stage started in s
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    /// Distance scrolled since the stage started in pixels
+    pub fn distance(&self) -> f32 {
+        self.distance
+    }
+
+    pub fn scroll_speed(&self) -> f32 {
+        self.scroll_speed
+    }
+
+    pub fn boss_warning_active(&self) -> bool {
+        self.boss_warning > 0.0
+    }
+
+    pub fn stage_complete(&self) -> bool {
+        self.stage_complete
+    }
+
+    /// Advance the timeline by `dt` s, returns the events triggered meanwhile in file order
+    pub fn advance(&mut self, dt: f32) -> Vec<DirectorEvent> {
+        if self.stage_complete {
+            return Vec::new();
+        }
+
+        self.time += dt;
+        self.distance += self.scroll_speed * dt;
+        self.boss_warning = f32::max(self.boss_warning - dt, 0.0);
+
+        let (time, distance) = (self.time, self.distance);
+        let (due, pending): (Vec<_>, Vec<_>) =
+            self.pending
+                .drain(..)
+                .partition(|(trigger, _)| match *trigger {
+                    Trigger::Time(t) => t <= time,
+                    Trigger::Distance(d) => d <= distance,
+                });
+        self.pending = pending;
+
+        let mut events = Vec::with_capacity(due.len());
+        for (_, event) in due {
+            match event {
+                DirectorEvent::BossWarning(duration) => self.boss_warning = duration,
+                DirectorEvent::EndStage => {
+                    self.stage_complete = true;
+                    self.pending.clear();
+                }
+                _ => (),
+            }
+            events.push(event);
+            if self.stage_complete {
+                break;
+            }
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, path::PathBuf};
+
+    use super::*;
+    use crate::assets::{Assets, HeadlessAssetLoader};
+
+    /// Level file in the temporary directory, removed when dropped
+    struct LevelFile(PathBuf);
+
+    impl LevelFile {
+        fn new(name: &str, content: &str) -> Self {
+            let path =
+                env::temp_dir().join(format!("deimosreborn-{}-{}", std::process::id(), name));
+            fs::write(&path, content).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for LevelFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    /// Enemies with broken references, next to the ones of the game
+    const ENEMIES: &str = r#"{
+        "drone": (
+            sprite: "enemy_placeholder",
+            health: 3,
+            score: 100,
+            explosion_sprite: "explosion_placeholder",
+            explosion_sound: "explosion_placeholder",
+        ),
+        "silent": (
+            sprite: "enemy_placeholder",
+            health: 3,
+            score: 100,
+            explosion_sprite: "explosion_placeholder",
+            explosion_sound: "no_such_sound",
+        ),
+        "unarmed": (
+            sprite: "enemy_placeholder",
+            health: 3,
+            score: 100,
+            weapon: Some((pattern: "no_such_pattern", bullet_sprite: "enemy_bullet_placeholder")),
+            explosion_sprite: "explosion_placeholder",
+            explosion_sound: "explosion_placeholder",
+        ),
+    }"#;
+
+    /// Errors of validating `content` as the level file `name`
+    fn validation_errors(name: &str, content: &str) -> String {
+        let assets = Assets::load(&mut HeadlessAssetLoader::new()).unwrap();
+        let enemies = EnemyLibrary::parse(ENEMIES).unwrap();
+        let paths = PathLibrary::load("data/paths.ron").unwrap();
+        let patterns = PatternLibrary::load("data/patterns.ron").unwrap();
+        let particles = EmitterLibrary::load("data/particles.ron", &assets.catalog).unwrap();
+        let animations = StateMachineLibrary::load("data/animations.ron").unwrap();
+        let context = LevelContext {
+            catalog: &assets.catalog,
+            enemies: &enemies,
+            paths: &paths,
+            patterns: &patterns,
+            particles: &particles,
+            animations: &animations,
+        };
+
+        let file = LevelFile::new(name, content);
+        let error = validate_level(&file.0, &context).expect_err("level should be invalid");
+        format!("{:#}", error).replace(&file.0.display().to_string(), name)
+    }
+
+    #[test]
+    fn finds_the_lines_of_list_elements() {
+        let content = r##"// Brackets in comments: ( [ {
+(
+    name: "Brackets ( [ { \" in strings",
+    label: r#"Raw " ( [ strings"#,
+    other: r"raw (",
+    /* Block
+       comment [ */
+    key: '(',
+    quote: '\'',
+    events: [
+        (trigger: Time(0.0), event: EndStage),
+
+        (
+            trigger: Time(1.0),
+            event: Spawn(enemy: "[", position: (0.0, 0.0)),
+        ),
+        // Not an element: (
+        (trigger: Time(2.0), event: Music(file: r#"music ]"#)),
+    ],
+    nested: (events: [(a: 1)]),
+)
+"##;
+
+        assert_eq!(element_lines(content, &["events"]), [11, 13, 18]);
+        assert_eq!(element_lines(content, &["nested", "events"]), [20]);
+        assert!(element_lines(content, &["background", "planes"]).is_empty());
+    }
+
+    #[test]
+    fn finds_the_lines_of_the_stage_planes_and_events() {
+        let content = fs::read_to_string("data/levels/stage1.ron").unwrap();
+        let lines = SourceLines::scan(FilePath::new("stage1.ron"), &content);
+        let level: Level = ron::from_str(&content).unwrap();
+
+        assert_eq!(lines.planes.len(), level.background.unwrap().planes.len());
+        assert_eq!(lines.events.len(), level.events.len());
+        let source: Vec<&str> = content.lines().collect();
+        for line in lines.planes.iter().chain(&lines.events) {
+            assert!(
+                source[line - 1].trim_start().starts_with('('),
+                "line {}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn reports_unknown_names_with_their_line() {
+        let errors = validation_errors(
+            "unknown-names.ron",
+            r##"(
+    name: "Broken ( [ \" level",
+    background: Some((
+        color: (0, 0, 0),
+        planes: [
+            (sprite: "no_such_sprite", tiles: [[0]]),
+        ],
+    )),
+    events: [
+        (trigger: Time(0.0), event: Spawn(enemy: "drone", position: (0.0, 0.0))),
+        (trigger: Time(1.0), event: Spawn(enemy: "no_such_enemy", position: (0.0, 0.0))),
+        // Brackets in strings don't shift the lines: r#"(["#
+        (trigger: Time(2.0), event: Spawn(enemy: "drone", position: (0.0, 0.0), path: Some("no_such_path"))),
+        (
+            trigger: Time(3.0),
+            event: Spawn(enemy: "silent", position: (0.0, 0.0)),
+        ),
+        (trigger: Time(4.0), event: Spawn(enemy: "unarmed", position: (0.0, 0.0))),
+        (trigger: Time(5.0), event: EndStage),
+    ],
+)
+"##,
+        );
+
+        let errors: Vec<&str> = errors.lines().collect();
+        assert_eq!(errors.len(), 5, "{:#?}", errors);
+        assert!(errors[0].ends_with(
+            "unknown-names.ron:6: background plane 0: unknown sprite \"no_such_sprite\""
+        ));
+        assert!(errors[1].starts_with("unknown-names.ron:11: event 1"));
+        assert!(errors[1].ends_with("unknown enemy \"no_such_enemy\""));
+        assert!(errors[2].starts_with("unknown-names.ron:13: event 2"));
+        assert!(errors[2].ends_with("unknown path \"no_such_path\""));
+        assert!(errors[3].starts_with("unknown-names.ron:14: event 3"));
+        assert!(errors[3].ends_with("unknown sound \"no_such_sound\""));
+        assert!(errors[4].starts_with("unknown-names.ron:18: event 4"));
+        assert!(errors[4].ends_with("unknown pattern \"no_such_pattern\""));
+    }
+
+    #[test]
+    fn reports_missing_files() {
+        let errors = validation_errors(
+            "missing-music.ron",
+            r#"(
+    name: "Silent",
+    events: [(trigger: Time(0.0), event: Music(file: "no/such/music.ogg"))],
+)
+"#,
+        );
+        assert!(
+            errors
+                .lines()
+                .any(|line| line == "missing file no/such/music.ogg"),
+            "{}",
+            errors
+        );
+    }
+}
@@ -0,0 +1,31 @@
+pub mod animation;
+pub mod assets;
+pub mod errors;
+pub mod font;
+pub mod game;
+pub mod headless;
+pub mod hud;
+pub mod level;
+pub mod particle;
+pub mod path;
+pub mod pattern;
+pub mod replay;
+pub mod state_machine;
+pub mod tiled;
+
+pub mod sound;
+pub mod sprite;
+
+pub mod component;
+pub mod entity;
+pub mod resource;
+pub mod system;
+
+pub use game::{Game, GameBuilder};
+
+/// Game coordinate system: sprite dimensions and physics are using these
+pub const GAME_WIDTH: u32 = 640;
+pub const GAME_HEIGHT: u32 = 480;
+
+pub const FRAME_RATE_GAME: u32 = 60;
+pub const FRAME_RATE_RENDER: u32 = 60;
@@ -1,53 +1,160 @@
-mod errors;
-
-mod sound;
-mod sprite;
-
-mod component;
-mod entity;
-mod resource;
-mod system;
-
 use log::{debug, error, info, trace, warn};
 use simple_logger::SimpleLogger;
 
 use sdl2::event::Event;
-use sdl2::keyboard::Keycode;
+use sdl2::keyboard::{Keycode, Mod};
+
+use specs::{Join, WorldExt};
+
+use anyhow::{bail, Context, Result};
+
+use deimosreborn::{
+    errors,
+    game::{physics_tick_duration, validate_level},
+    headless::{InputScript, InputSource, Simulation},
+    replay::Replay,
+    resource::{
+        debug::DebugOverlay,
+        display::{Display, Scaling, WindowMode},
+        player_input::PlayerInput,
+        screenshot::{Resolution, Screenshots},
+        timing::Timing,
+    },
+    system::render,
+    GameBuilder, FRAME_RATE_GAME, FRAME_RATE_RENDER,
+};
 
-use specs::{DispatcherBuilder, World, WorldExt};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
-use anyhow::{Context, Result};
+/// Display settings the window is created with
+const DISPLAY_FILE: &str = "data/display.ron";
+
+/// Sounds that can play at the same time, enough for a burst of explosions over the ion cannon
+const MIXER_CHANNELS: i32 = 32;
+
+#[derive(Default)]
+struct Options {
+    /// Simulate a number of physics ticks without window and audio
+    headless_ticks: Option<u64>,
+    /// Input script driving the headless simulation
+    script: Option<PathBuf>,
+    /// Record player input to a replay file
+    record: Option<PathBuf>,
+    /// Play back player input from a replay file
+    replay: Option<PathBuf>,
+    /// Level file to play instead of the first stage
+    level: Option<PathBuf>,
+    /// Check a level file and exit
+    validate_level: Option<PathBuf>,
+    /// Scaling overriding the one of the display settings
+    scaling: Option<Scaling>,
+}
 
-use component::{
-    bullet_physics::BulletPhysicsComponent, player_animation::PlayerAnimationComponent,
-    player_physics::PlayerPhysicsComponent, player_weapon::PlayerWeaponComponent,
-    position::PositionComponent, sprite::SpriteComponent, track_position::TrackPositionComponent,
-};
-use entity::player::Player;
-use resource::{player_input::PlayerInput, sound::AudioInterface, timing::Timing};
-use system::{
-    bullet_physics::BulletPhysicsSystem, player_animation::PlayerAnimationSystem,
-    player_movement::PlayerMovementSystem, player_weapon::PlayerWeaponSystem, render::RenderSystem,
-    track_position::PositionTrackSystem,
-};
+fn parse_args() -> Result<Options> {
+    let mut options = Options::default();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--headless" => {
+                let ticks = args
+                    .next()
+                    .context("--headless expects the number of ticks to simulate")?
+                    .parse()
+                    .context("--headless expects the number of ticks to simulate")?;
+                options.headless_ticks = Some(ticks);
+            }
+            "--script" => {
+                options.script = Some(PathBuf::from(
+                    args.next().context("--script expects a file name")?,
+                ));
+            }
+            "--record" => {
+                options.record = Some(PathBuf::from(
+                    args.next().context("--record expects a file name")?,
+                ));
+            }
+            "--replay" => {
+                options.replay = Some(PathBuf::from(
+                    args.next().context("--replay expects a file name")?,
+                ));
+            }
+            "--level" => {
+                options.level = Some(PathBuf::from(
+                    args.next().context("--level expects a file name")?,
+                ));
+            }
+            "--validate-level" => {
+                options.validate_level = Some(PathBuf::from(
+                    args.next()
+                        .context("--validate-level expects a file name")?,
+                ));
+            }
+            "--scaling" => {
+                options.scaling = Some(match args.next().as_deref() {
+                    Some("integer") => Scaling::Integer,
+                    Some("aspect") => Scaling::Aspect,
+                    _ => bail!("--scaling expects \"integer\" or \"aspect\""),
+                });
+            }
+            _ => bail!("Unknown argument: {}", arg),
+        }
+    }
 
-use sound::{SoundId, SoundLibrary};
-use sprite::{Sprite, SpriteDescription, SpriteManager};
+    if options.script.is_some() && options.headless_ticks.is_none() {
+        bail!("--script is only supported with --headless");
+    }
+    if options.script.is_some() && options.replay.is_some() {
+        bail!("--script and --replay can't be used together");
+    }
+    if options.record.is_some() && options.replay.is_some() {
+        bail!("--record and --replay can't be used together");
+    }
 
-use std::sync::mpsc::channel;
-use std::time::{Duration, Instant};
+    Ok(options)
+}
 
-/// Game coordinate system: sprite dimensions and physics are using these
-const GAME_WIDTH: u32 = 640;
-const GAME_HEIGHT: u32 = 480;
+fn game_builder(level: &Option<PathBuf>) -> GameBuilder {
+    match level {
+        Some(file) => GameBuilder::new().with_level(file),
+        None => GameBuilder::new(),
+    }
+}
 
-/// Window coordinate system: scaling to actual window dimensions happening at render step
-const WINDOW_SCALE: u32 = 2;
-const WINDOW_WIDTH: u32 = GAME_WIDTH * WINDOW_SCALE;
-const WINDOW_HEIGHT: u32 = GAME_HEIGHT * WINDOW_SCALE;
+fn run_headless(ticks: u64, options: Options) -> Result<()> {
+    let input: Box<dyn InputSource> = match (&options.script, &options.replay) {
+        (Some(path), _) => Box::new(InputScript::from_file(path)?),
+        (_, Some(path)) => Box::new(Replay::load(path)?),
+        (None, None) => Box::new(InputScript::default()),
+    };
+    let mut recording = options.record.as_ref().map(|_| Replay::new());
+
+    let mut simulation = Simulation::from_game(game_builder(&options.level).build_headless()?);
+
+    let start = Instant::now();
+    for _ in 0..ticks {
+        let input = input.input_at(simulation.tick());
+        if let Some(recording) = &mut recording {
+            recording.record(input);
+        }
+        simulation.step(input)?;
+    }
+    let elapsed = start.elapsed();
 
-const FRAME_RATE_GAME: u32 = 60;
-const FRAME_RATE_RENDER: u32 = 60;
+    println!(
+        "Simulated {} ticks in {} ms, {} entities alive",
+        simulation.tick(),
+        elapsed.as_millis(),
+        simulation.world().entities().join().count()
+    );
+
+    if let (Some(recording), Some(path)) = (recording, options.record) {
+        recording.save(path)?;
+    }
+
+    Ok(())
+}
 
 fn main() -> Result<()> {
     SimpleLogger::new()
@@ -55,6 +162,19 @@ fn main() -> Result<()> {
         .env()
         .init()?;
 
+    let options = parse_args()?;
+    if let Some(file) = &options.validate_level {
+        validate_level(file)?;
+        println!("Level {} is valid", file.display());
+        return Ok(());
+    }
+    if let Some(ticks) = options.headless_ticks {
+        return run_headless(ticks, options);
+    }
+
+    let replay = options.replay.as_ref().map(Replay::load).transpose()?;
+    let mut recording = options.record.as_ref().map(|_| Replay::new());
+
     let sdl_context = sdl2::init()
         .map_err(errors::SdlError::InitError)
         .context("Failed to initialize SDL2")?;
@@ -85,133 +205,52 @@ fn main() -> Result<()> {
     sdl2::mixer::open_audio(44100, sdl2_sys::mixer::MIX_DEFAULT_FORMAT as u16, 2, 1024)
         .map_err(errors::SdlError::InitError)
         .context("Failed to open audio driver")?;
+    sdl2::mixer::allocate_channels(MIXER_CHANNELS);
 
-    let window = video_subsystem
-        .window("Deimos Reborn", WINDOW_WIDTH, WINDOW_HEIGHT)
-        .position_centered()
-        .build()
-        .with_context(|| {
-            format!(
-                "Could not create main window with dimensions {}x{}",
-                WINDOW_WIDTH, WINDOW_HEIGHT
-            )
-        })?;
-
-    let canvas = window
-        .into_canvas()
-        .accelerated()
-        .present_vsync()
-        .build()
-        .context("Failed to create canvas")?;
-
-    sdl2::hint::set("SDL_HINT_RENDER_VSYNC", "1");
-
-    let texture_creator = canvas.texture_creator();
-    let mut sprite_manager = SpriteManager::new();
-
-    let player_sprite = Sprite::from_gif(
-        SpriteDescription {
-            number_of_frames: 7,
-            border_left: 3,
-            border_up: 3,
-            frame_dimensions: (53, 43),
-        },
-        "assets/ Data/Paks/Game/im08/Player 1 Orange IC[pl1o].gif",
-        "assets/ Data/Paks/Game/im08/Player 1 Orange IA[PL1O].gif",
-        &texture_creator,
-    )?;
-    let player_sprite_id = sprite_manager.insert(player_sprite);
-
-    let ion_cannon_bullet_sprite = Sprite::from_gif(
-        SpriteDescription {
-            number_of_frames: 36,
-            border_left: 3,
-            border_up: 3,
-            frame_dimensions: (20, 20),
-        },
-        "assets/ Data/Paks/Game/im08/Ion Cannon Bullet IC[icbu].gif",
-        "assets/ Data/Paks/Game/im08/Ion Cannon Bullet IA[ICBU].gif",
-        &texture_creator,
-    )?;
-
-    let bullet_sprite_id = sprite_manager.insert(ion_cannon_bullet_sprite);
-
-    let ion_cannon_glow_sprite = Sprite::from_gif(
-        SpriteDescription {
-            number_of_frames: 1,
-            border_left: 66,
-            border_up: 3,
-            frame_dimensions: (70, 70),
-        },
-        "assets/ Data/Paks/Game/im08/Ion Cannon IC[ioca].gif",
-        "assets/ Data/Paks/Game/im08/Ion Cannon IA[IOCA].gif",
-        &texture_creator,
-    )?;
-
-    let glow_sprite_id = sprite_manager.insert(ion_cannon_glow_sprite);
-
-    let mut sound_library = SoundLibrary::new();
-
-    let sound_ion_cannon_bullet =
-        sdl2::mixer::Chunk::from_file("assets/ Data/Paks/Audio/Ion-Cannon-Bullet_icbu_.wav")
-            .map_err(errors::SdlError::SoundLoadError)?;
-
-    let bullet_sound_id = sound_library.insert(sound_ion_cannon_bullet);
-
-    let audio_channel = sdl2::mixer::Channel::all();
-    let (audio_sender, audio_receiver) = channel::<SoundId>();
-
-    let mut world = World::new();
-    world.insert(PlayerInput::default());
-    world.insert(Timing::default());
-    world.insert(AudioInterface::new(audio_sender));
-    world.register::<BulletPhysicsComponent>();
-    world.register::<PlayerAnimationComponent>();
-    world.register::<PlayerPhysicsComponent>();
-    world.register::<PlayerWeaponComponent>();
-    world.register::<PositionComponent>();
-    world.register::<SpriteComponent>();
-    world.register::<TrackPositionComponent>();
-
-    {
-        let mut timing = world.write_resource::<Timing>();
-        timing.delta_time = Duration::from_nanos(1_000_000_000u64 / (FRAME_RATE_GAME as u64));
+    let mut display = Display::load(DISPLAY_FILE)?;
+    if let Some(scaling) = options.scaling {
+        display.scaling = scaling;
     }
 
-    Player::create_player(
-        &mut world,
-        player_sprite_id,
-        sprite_manager.get_description(player_sprite_id),
-        (GAME_WIDTH / 2) as f32,
-        (GAME_HEIGHT - 100) as f32,
-        bullet_sprite_id,
-        (14, 18), // FIXME: proper handling of hitboxes
-        bullet_sound_id,
-        glow_sprite_id,
-    );
-
-    let mut dispatcher_game = DispatcherBuilder::new()
-        .with(PlayerMovementSystem, "player_movement", &[])
-        .with(PlayerWeaponSystem, "player_weapon", &["player_movement"])
-        .with(BulletPhysicsSystem, "bullet_physics", &[])
-        .with(
-            PlayerAnimationSystem,
-            "player_animation",
-            &["player_movement", "player_weapon"],
-        )
-        .with(
-            PositionTrackSystem,
-            "position_track",
-            &["player_movement", "bullet_physics"],
+    let (window_width, window_height) = display.window_size();
+    let mut window_builder = video_subsystem.window("Deimos Reborn", window_width, window_height);
+    window_builder.position_centered().resizable();
+    match display.mode {
+        WindowMode::Windowed => (),
+        WindowMode::DesktopFullscreen => {
+            window_builder.fullscreen_desktop();
+        }
+        WindowMode::Fullscreen => {
+            window_builder.fullscreen();
+        }
+    }
+    let window = window_builder.build().with_context(|| {
+        format!(
+            "Could not create main window with dimensions {}x{}",
+            window_width, window_height
         )
-        .build();
+    })?;
+
+    let mut canvas_builder = window.into_canvas().accelerated();
+    if display.vsync {
+        canvas_builder = canvas_builder.present_vsync();
+    }
+    let canvas = canvas_builder.build().context("Failed to create canvas")?;
+    let vsync_switchable = render::vsync_switchable();
+    if !vsync_switchable {
+        let version = sdl2::version::version();
+        warn!(target: "main", "SDL {} can't switch vsync while running, F10 is disabled", version);
+    }
 
-    let mut dispatcher_render = DispatcherBuilder::new()
-        .with_thread_local(RenderSystem::new(canvas, sprite_manager))
-        .build();
+    let texture_creator = canvas.texture_creator();
+    let mut game = game_builder(&options.level)
+        .with_display(display)
+        .build(canvas, &texture_creator)?;
 
     let mut event_pump = sdl_context.event_pump().unwrap();
 
+    let mut player_input = PlayerInput::default();
+
     let mut next_physics_tick = Instant::now();
     let mut prev_frame_start = Instant::now();
 
@@ -224,6 +263,9 @@ fn main() -> Result<()> {
             (frame_start - next_physics_tick).as_micros()
         );
         prev_frame_start = frame_start;
+        game.world()
+            .write_resource::<DebugOverlay>()
+            .record_frame(frame_start);
 
         // Run physics update, multiple steps if needed
         loop {
@@ -231,13 +273,15 @@ fn main() -> Result<()> {
             if now > next_physics_tick {
                 let elapsed = now - next_physics_tick;
 
-                next_physics_tick +=
-                    Duration::from_nanos(1_000_000_000u64 / (FRAME_RATE_GAME as u64));
+                next_physics_tick += physics_tick_duration();
 
                 // If we missed more than a few updates, take the loss and re-synchronize
                 if elapsed > Duration::from_nanos((3 * 1_000_000_000u32 / FRAME_RATE_GAME) as u64) {
                     // Too much time has elapsed, fast-forward
                     warn!(target: "main loop", "Missed too many physics updates, elapsed: {} us", elapsed.as_micros());
+                    game.world()
+                        .write_resource::<DebugOverlay>()
+                        .record_missed_update();
                     continue;
                 }
 
@@ -246,26 +290,88 @@ fn main() -> Result<()> {
                     elapsed.as_micros()
                 );
 
-                {
-                    let mut timing = world.write_resource::<Timing>();
-                    timing.physics_tick = next_physics_tick;
-
-                    let mut player_input = world.write_resource::<PlayerInput>();
-
-                    for event in event_pump.poll_iter() {
-                        match event {
-                            Event::Quit { .. }
-                            | Event::KeyDown {
-                                keycode: Some(Keycode::Escape),
-                                ..
-                            } => break 'running,
-                            _ => player_input.update_player_input(event),
+                for event in event_pump.poll_iter() {
+                    match event {
+                        Event::Quit { .. }
+                        | Event::KeyDown {
+                            keycode: Some(Keycode::Escape),
+                            ..
+                        } => break 'running,
+                        Event::KeyDown {
+                            keycode: Some(Keycode::F3),
+                            repeat: false,
+                            ..
+                        } => game.world().write_resource::<DebugOverlay>().toggle(),
+                        Event::KeyDown {
+                            keycode: Some(Keycode::F9),
+                            repeat: false,
+                            ..
+                        } => game
+                            .world()
+                            .write_resource::<Display>()
+                            .cycle_window_scale(),
+                        Event::KeyDown {
+                            keycode: Some(Keycode::F10),
+                            repeat: false,
+                            ..
+                        } if vsync_switchable => {
+                            game.world().write_resource::<Display>().toggle_vsync()
                         }
+                        Event::KeyDown {
+                            keycode: Some(Keycode::F11),
+                            repeat: false,
+                            ..
+                        } => game.world().write_resource::<Display>().cycle_mode(),
+                        Event::KeyDown {
+                            keycode: Some(Keycode::F12),
+                            keymod,
+                            repeat: false,
+                            ..
+                        } => {
+                            // F12 captures the window as presented, Shift+F12 the game in its
+                            // native resolution, both with the HUD
+                            let resolution = if keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) {
+                                Resolution::Native
+                            } else {
+                                Resolution::Window
+                            };
+                            game.world()
+                                .write_resource::<Screenshots>()
+                                .request(resolution);
+                        }
+                        Event::KeyDown {
+                            keycode: Some(Keycode::Return),
+                            keymod,
+                            repeat: false,
+                            ..
+                        } if keymod.intersects(Mod::LALTMOD | Mod::RALTMOD) => {
+                            game.world().write_resource::<Display>().toggle_fullscreen()
+                        }
+                        _ => player_input.update_player_input(event),
                     }
                 }
 
-                dispatcher_game.dispatch(&world);
-                world.maintain();
+                let input = match &replay {
+                    Some(replay) if game.ticks() < replay.len() as u64 => {
+                        replay.input_at(game.ticks())
+                    }
+                    _ => player_input,
+                };
+
+                if let Some(recording) = &mut recording {
+                    recording.record(input);
+                }
+
+                game.tick(input, next_physics_tick);
+                game.world()
+                    .write_resource::<DebugOverlay>()
+                    .record_tick(Instant::now());
+
+                if let Some(replay) = &replay {
+                    if game.ticks() == replay.len() as u64 {
+                        info!(target: "main loop", "Replay finished, switching to keyboard input");
+                    }
+                }
             } else {
                 // Not enough time passed, skip..
                 break;
@@ -273,26 +379,39 @@ fn main() -> Result<()> {
         }
 
         // Sounds
-        for sound in audio_receiver.try_iter() {
-            audio_channel
-                .play(sound_library.get(sound), 0)
-                .map_err(errors::SdlError::AudioPlayError)?;
-        }
+        game.play_sounds()?;
 
         // Render
-        dispatcher_render.dispatch(&world);
+        game.render();
 
-        let frame_end = Instant::now();
+        for (path, result) in game.world().write_resource::<Screenshots>().take_finished() {
+            match result {
+                Ok(()) => info!(target: "main loop", "Saved screenshot {}", path.display()),
+                Err(e) => error!(target: "main loop", "{:?}", e),
+            }
+        }
 
-        // Try to recover vsync
-        if (frame_end - frame_start).as_millis() > 8 {
-            let next_vsync =
-                frame_end + Duration::from_nanos(1_000_000_000u64 / FRAME_RATE_RENDER as u64);
+        let frame_end = Instant::now();
+        let frame_duration = Duration::from_nanos(1_000_000_000u64 / FRAME_RATE_RENDER as u64);
+        let vsync = game.world().read_resource::<Display>().vsync;
+
+        if !vsync {
+            // Presenting doesn't block, limit the frame rate and interpolate to the actual
+            // render time
+            game.world().write_resource::<Timing>().next_vsync = None;
+            if let Some(remaining) =
+                (frame_start + frame_duration).checked_duration_since(frame_end)
+            {
+                std::thread::sleep(remaining);
+            }
+        } else if (frame_end - frame_start).as_millis() > 8 {
+            // Try to recover vsync
+            let next_vsync = frame_end + frame_duration;
             trace!(target: "main loop", "next vsync: {:?}", next_vsync);
-            let mut timing = world.write_resource::<Timing>();
+            let mut timing = game.world().write_resource::<Timing>();
             timing.next_vsync = Some(next_vsync);
         } else {
-            let mut timing = world.write_resource::<Timing>();
+            let mut timing = game.world().write_resource::<Timing>();
             if let Some(next_vsync) = timing.next_vsync {
                 if frame_end > next_vsync {
                     warn!(target: "main loop", "reset vsync");
@@ -308,5 +427,9 @@ fn main() -> Result<()> {
         );
     }
 
+    if let (Some(recording), Some(path)) = (recording, options.record) {
+        recording.save(path)?;
+    }
+
     Ok(())
 }
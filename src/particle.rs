@@ -0,0 +1,233 @@
+use std::{collections::HashMap, fs, path::Path, sync::Arc};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+use crate::{assets::AssetCatalog, sprite::SpriteId, system::render::Layer};
+
+/// Particle emitter as written in the particle file
+///
+/// Pairs are either (min, max), a value picked at random for every particle, or (start, end),
+/// interpolated over the lifetime of a particle.
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmitterDefinition {
+    /// Sprite of the particles, its (white) frames are modulated with the particle color
+    pub sprite: String,
+    pub layer: Layer,
+    /// Order within the layer, higher values are rendered on top
+    #[serde(default)]
+    pub z_order: i32,
+    /// Particles emitted at once when the emitter starts
+    #[serde(default)]
+    pub burst: u32,
+    /// Particles emitted per second
+    #[serde(default)]
+    pub rate: f32,
+    /// Time the emitter keeps emitting in s, forever if not given
+    #[serde(default)]
+    pub duration: Option<f32>,
+    /// Offset of the spawn point from the emitter position in pixels
+    #[serde(default)]
+    pub offset: (f32, f32),
+    /// Particles spawn at a random point within this distance of the spawn point in pixels
+    #[serde(default)]
+    pub radius: f32,
+    /// (min, max) lifetime in s
+    pub lifetime: (f32, f32),
+    /// Direction particles are emitted in, in degrees clockwise from the positive x axis
+    #[serde(default)]
+    pub direction: f32,
+    /// Max. deviation from the direction in degrees, 180 emits in all directions
+    #[serde(default)]
+    pub spread: f32,
+    /// (min, max) speed in pixels / s
+    pub speed: (f32, f32),
+    /// Acceleration in pixels / s^2
+    #[serde(default)]
+    pub gravity: (f32, f32),
+    /// Fraction of the velocity lost per second
+    #[serde(default)]
+    pub drag: f32,
+    /// (start, end) scale factor of the sprite
+    #[serde(default = "default_size")]
+    pub size: (f32, f32),
+    /// (start, end) opacity
+    #[serde(default = "default_alpha")]
+    pub alpha: (u8, u8),
+    /// (start, end) color
+    #[serde(default = "default_color")]
+    pub color: ((u8, u8, u8), (u8, u8, u8)),
+    /// Sprite frames per second, looping, the frames are spread over the lifetime if not given
+    #[serde(default)]
+    pub frame_rate: Option<f32>,
+}
+
+fn default_size() -> (f32, f32) {
+    (1.0, 1.0)
+}
+
+fn default_alpha() -> (u8, u8) {
+    (255, 255)
+}
+
+fn default_color() -> ((u8, u8, u8), (u8, u8, u8)) {
+    ((255, 255, 255), (255, 255, 255))
+}
+
+/// Particle emitter with its sprite resolved
+#[derive(Debug)]
+pub struct EmitterType {
+    pub sprite: SpriteId,
+    pub number_of_frames: usize,
+    pub definition: EmitterDefinition,
+}
+
+impl EmitterType {
+    fn from_definition(definition: EmitterDefinition, catalog: &AssetCatalog) -> Result<Self> {
+        let sprite = catalog
+            .sprite(&definition.sprite)
+            .with_context(|| format!("unknown sprite \"{}\"", definition.sprite))?;
+
+        let (min_lifetime, max_lifetime) = definition.lifetime;
+        if min_lifetime <= 0.0 || min_lifetime > max_lifetime {
+            bail!("lifetime must be positive, with min <= max");
+        }
+        if definition.speed.0 > definition.speed.1 {
+            bail!("speed must have min <= max");
+        }
+        if definition.rate < 0.0 || definition.drag < 0.0 || definition.radius < 0.0 {
+            bail!("rate, drag and radius must not be negative");
+        }
+        if definition.burst == 0 && definition.rate == 0.0 {
+            bail!("emitter neither has a burst nor a rate");
+        }
+        if matches!(definition.frame_rate, Some(frame_rate) if frame_rate <= 0.0) {
+            bail!("frame_rate must be positive");
+        }
+
+        Ok(Self {
+            sprite: sprite.id,
+            number_of_frames: sprite.description.number_of_frames,
+            definition,
+        })
+    }
+
+    /// Sprite frame of a particle
+    pub fn frame(&self, particle: &Particle) -> usize {
+        match self.definition.frame_rate {
+            Some(frame_rate) => (particle.age * frame_rate) as usize % self.number_of_frames,
+            None => ((particle.progress() * self.number_of_frames as f32) as usize)
+                .min(self.number_of_frames - 1),
+        }
+    }
+
+    pub fn size(&self, particle: &Particle) -> f32 {
+        let (start, end) = self.definition.size;
+        lerp(start, end, particle.progress())
+    }
+
+    pub fn alpha(&self, particle: &Particle) -> u8 {
+        let (start, end) = self.definition.alpha;
+        lerp(start as f32, end as f32, particle.progress()).round() as u8
+    }
+
+    pub fn color(&self, particle: &Particle) -> (u8, u8, u8) {
+        let (start, end) = self.definition.color;
+        let t = particle.progress();
+        let channel = |start: u8, end: u8| lerp(start as f32, end as f32, t).round() as u8;
+
+        (
+            channel(start.0, end.0),
+            channel(start.1, end.1),
+            channel(start.2, end.2),
+        )
+    }
+}
+
+fn lerp(start: f32, end: f32, t: f32) -> f32 {
+    start + (end - start) * t
+}
+
+/// Small deterministic pseudo random generator, so replays show the same particles
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // Avoid similar sequences for consecutive seeds
+        Self(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ 0x5eed)
+    }
+
+    /// Uniformly distributed in [0, 1)
+    pub fn next_f32(&mut self) -> f32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Uniformly distributed between `min` and `max`
+    pub fn range(&mut self, (min, max): (f32, f32)) -> f32 {
+        lerp(min, max, self.next_f32())
+    }
+}
+
+/// Single particle, not an entity of its own
+#[derive(Debug, Clone)]
+pub struct Particle {
+    pub x: f32,
+    pub y: f32,
+    pub previous_x: f32,
+    pub previous_y: f32,
+    pub vx: f32,
+    pub vy: f32,
+    /// Time since the particle was emitted in s
+    pub age: f32,
+    pub lifetime: f32,
+}
+
+impl Particle {
+    /// Fraction of the lifetime passed
+    pub fn progress(&self) -> f32 {
+        (self.age / self.lifetime).min(1.0)
+    }
+}
+
+/// All particle emitters, by name
+#[derive(Default)]
+pub struct EmitterLibrary {
+    emitters: HashMap<String, Arc<EmitterType>>,
+}
+
+impl EmitterLibrary {
+    /// Load emitters from a RON file mapping names to emitters, sprites are looked up in
+    /// `catalog`
+    pub fn load<P: AsRef<Path>>(file: P, catalog: &AssetCatalog) -> Result<Self> {
+        let file = file.as_ref();
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read particle file {}", file.display()))?;
+
+        Self::parse(&content, catalog)
+            .with_context(|| format!("Invalid particle file {}", file.display()))
+    }
+
+    pub fn parse(content: &str, catalog: &AssetCatalog) -> Result<Self> {
+        let definitions: HashMap<String, EmitterDefinition> = ron::from_str(content)?;
+
+        let emitters = definitions
+            .into_iter()
+            .map(|(name, definition)| {
+                let emitter = EmitterType::from_definition(definition, catalog)
+                    .with_context(|| format!("invalid emitter \"{}\"", name))?;
+                Ok((name, Arc::new(emitter)))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { emitters })
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<EmitterType>> {
+        self.emitters.get(name).cloned()
+    }
+}
@@ -0,0 +1,268 @@
+use std::{collections::HashMap, f32::consts::PI, fs, path::Path as FilePath, sync::Arc};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+/// Part of a movement path, points are relative to the position the path was started at
+#[derive(Debug, Clone, Deserialize)]
+pub enum Segment {
+    /// Straight line to `to`
+    Line { to: (f32, f32), duration: f32 },
+    /// Line to `to`, oscillating perpendicular to it
+    Sine {
+        to: (f32, f32),
+        amplitude: f32,
+        periods: f32,
+        duration: f32,
+    },
+    /// Bezier curve to `to` (one control point: quadratic, two: cubic, ...)
+    Bezier {
+        controls: Vec<(f32, f32)>,
+        to: (f32, f32),
+        duration: f32,
+    },
+    /// Catmull-Rom spline through all `points`, each span taking the same time
+    CatmullRom {
+        points: Vec<(f32, f32)>,
+        duration: f32,
+    },
+    /// Stay at the current point
+    Hold { duration: f32 },
+}
+
+impl Segment {
+    fn duration(&self) -> f32 {
+        match self {
+            Segment::Line { duration, .. }
+            | Segment::Sine { duration, .. }
+            | Segment::Bezier { duration, .. }
+            | Segment::CatmullRom { duration, .. }
+            | Segment::Hold { duration } => *duration,
+        }
+    }
+
+    fn end(&self, start: (f32, f32)) -> (f32, f32) {
+        match self {
+            Segment::Line { to, .. } | Segment::Sine { to, .. } | Segment::Bezier { to, .. } => *to,
+            Segment::CatmullRom { points, .. } => *points.last().unwrap_or(&start),
+            Segment::Hold { .. } => start,
+        }
+    }
+
+    /// Point at `s` (0.0 to 1.0) along the segment
+    fn point_at(&self, start: (f32, f32), s: f32) -> (f32, f32) {
+        match self {
+            Segment::Line { to, .. } => lerp(start, *to, s),
+            Segment::Sine {
+                to,
+                amplitude,
+                periods,
+                ..
+            } => {
+                let (x, y) = lerp(start, *to, s);
+                let (dx, dy) = (to.0 - start.0, to.1 - start.1);
+                let length = (dx * dx + dy * dy).sqrt();
+                if length == 0.0 {
+                    return (x, y);
+                }
+
+                let offset = amplitude * (2.0 * PI * periods * s).sin();
+                (x - dy / length * offset, y + dx / length * offset)
+            }
+            Segment::Bezier { controls, to, .. } => {
+                // De Casteljau's algorithm
+                let mut points: Vec<_> = std::iter::once(start)
+                    .chain(controls.iter().copied())
+                    .chain(std::iter::once(*to))
+                    .collect();
+                while points.len() > 1 {
+                    for i in 0..points.len() - 1 {
+                        points[i] = lerp(points[i], points[i + 1], s);
+                    }
+                    points.pop();
+                }
+                points[0]
+            }
+            Segment::CatmullRom { points, .. } => {
+                let spans = points.len();
+                let position = s * spans as f32;
+                let span = (position as usize).min(spans - 1);
+                // Start and end point are duplicated to get tangents at the ends
+                let point = |i: isize| match i {
+                    i if i <= 0 => start,
+                    i => points[(i as usize - 1).min(spans - 1)],
+                };
+
+                let i = span as isize;
+                catmull_rom(
+                    point(i - 1),
+                    point(i),
+                    point(i + 1),
+                    point(i + 2),
+                    position - span as f32,
+                )
+            }
+            Segment::Hold { .. } => start,
+        }
+    }
+}
+
+/// What happens once an entity reaches the end of its path
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Deserialize)]
+pub enum PathEnd {
+    /// Stay at the last point
+    #[default]
+    Stop,
+    /// Start over from the beginning (the path should end where it started)
+    Loop,
+    /// Delete the entity
+    Despawn,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PathDefinition {
+    segments: Vec<Segment>,
+    #[serde(default)]
+    end: PathEnd,
+}
+
+/// Movement path, the position only depends on the time since the path was started
+#[derive(Debug)]
+pub struct Path {
+    segments: Vec<Segment>,
+    /// Start point and start time of every segment
+    starts: Vec<((f32, f32), f32)>,
+    duration: f32,
+    end: PathEnd,
+}
+
+impl Path {
+    fn from_definition(definition: PathDefinition) -> Result<Self> {
+        if definition.segments.is_empty() {
+            bail!("path has no segments");
+        }
+
+        let mut starts = Vec::with_capacity(definition.segments.len());
+        let mut point = (0.0, 0.0);
+        let mut time = 0.0;
+        for (i, segment) in definition.segments.iter().enumerate() {
+            if segment.duration() <= 0.0 || !segment.duration().is_finite() {
+                bail!("segment {} has an invalid duration", i);
+            }
+            if let Segment::CatmullRom { points, .. } = segment {
+                if points.is_empty() {
+                    bail!("spline segment {} has no points", i);
+                }
+            }
+
+            starts.push((point, time));
+            point = segment.end(point);
+            time += segment.duration();
+        }
+
+        Ok(Self {
+            segments: definition.segments,
+            starts,
+            duration: time,
+            end: definition.end,
+        })
+    }
+
+    /// Total time in s
+    pub fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    pub fn end(&self) -> PathEnd {
+        self.end
+    }
+
+    /// Offset from the start point after `time` s, `None` once a non-looping path has ended
+    pub fn offset_at(&self, time: f32) -> Option<(f32, f32)> {
+        let time = match self.end {
+            PathEnd::Loop => time.rem_euclid(self.duration),
+            PathEnd::Stop | PathEnd::Despawn if time >= self.duration => return None,
+            PathEnd::Stop | PathEnd::Despawn => time,
+        };
+
+        // Last segment starting before `time`
+        let idx = self
+            .starts
+            .partition_point(|(_, start_time)| *start_time <= time)
+            .saturating_sub(1);
+        let (start, start_time) = self.starts[idx];
+        let segment = &self.segments[idx];
+
+        let s = ((time - start_time) / segment.duration()).clamp(0.0, 1.0);
+        Some(segment.point_at(start, s))
+    }
+
+    /// Offset at the very end of the path
+    pub fn last_offset(&self) -> (f32, f32) {
+        let (start, _) = *self.starts.last().expect("path should have segments");
+        self.segments
+            .last()
+            .expect("path should have segments")
+            .end(start)
+    }
+}
+
+/// All movement paths, by name
+#[derive(Default)]
+pub struct PathLibrary {
+    paths: HashMap<String, Arc<Path>>,
+}
+
+impl PathLibrary {
+    /// Load paths from a RON file mapping names to paths
+    pub fn load<P: AsRef<FilePath>>(file: P) -> Result<Self> {
+        let file = file.as_ref();
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read path file {}", file.display()))?;
+
+        Self::parse(&content).with_context(|| format!("Invalid path file {}", file.display()))
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        let definitions: HashMap<String, PathDefinition> = ron::from_str(content)?;
+
+        let paths = definitions
+            .into_iter()
+            .map(|(name, definition)| {
+                let path = Path::from_definition(definition)
+                    .with_context(|| format!("invalid path \"{}\"", name))?;
+                Ok((name, Arc::new(path)))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { paths })
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<Path>> {
+        self.paths.get(name).cloned()
+    }
+}
+
+fn lerp(a: (f32, f32), b: (f32, f32), s: f32) -> (f32, f32) {
+    (a.0 + (b.0 - a.0) * s, a.1 + (b.1 - a.1) * s)
+}
+
+/// Point between `p1` and `p2` of a uniform Catmull-Rom spline
+fn catmull_rom(
+    p0: (f32, f32),
+    p1: (f32, f32),
+    p2: (f32, f32),
+    p3: (f32, f32),
+    s: f32,
+) -> (f32, f32) {
+    let s2 = s * s;
+    let s3 = s2 * s;
+    let axis = |a: f32, b: f32, c: f32, d: f32| {
+        0.5 * (2.0 * b
+            + (c - a) * s
+            + (2.0 * a - 5.0 * b + 4.0 * c - d) * s2
+            + (3.0 * b - a - 3.0 * c + d) * s3)
+    };
+
+    (axis(p0.0, p1.0, p2.0, p3.0), axis(p0.1, p1.1, p2.1, p3.1))
+}
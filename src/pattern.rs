@@ -0,0 +1,391 @@
+use std::{collections::HashMap, fs, path::Path, sync::Arc};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+/// Arrangement of the bullets of a single volley, angles are in degrees
+#[derive(Debug, Clone, Deserialize)]
+pub enum PatternKind {
+    /// One bullet
+    Single,
+    /// `count` bullets spread evenly over `angle`
+    Spread { count: u32, angle: f32 },
+    /// `count` bullets spread evenly over the full circle
+    Ring { count: u32 },
+    /// Ring of `arms` bullets, rotating by `rotation` degrees / s
+    Spiral { arms: u32, rotation: f32 },
+}
+
+/// Direction the center of a volley points at
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Deserialize)]
+pub enum Aim {
+    /// Straight down
+    #[default]
+    Down,
+    /// At the player, straight down if there is none
+    Player,
+}
+
+/// Bullet pattern of an enemy weapon, times are in s
+#[derive(Debug, Clone, Deserialize)]
+pub struct BulletPattern {
+    pub kind: PatternKind,
+    #[serde(default)]
+    pub aim: Aim,
+    /// Bullet velocity in pixels / s
+    pub speed: f32,
+    /// Time between two bursts
+    pub interval: f32,
+    /// Volleys per burst
+    #[serde(default = "default_burst")]
+    pub burst: u32,
+    /// Time between the volleys of a burst
+    #[serde(default)]
+    pub burst_interval: f32,
+    /// Time until the first burst
+    #[serde(default)]
+    pub delay: f32,
+}
+
+fn default_burst() -> u32 {
+    1
+}
+
+impl BulletPattern {
+    fn validate(&self) -> Result<()> {
+        if !self.speed.is_finite() || self.speed < 0.0 {
+            bail!("speed must not be negative");
+        }
+        if !self.interval.is_finite() || self.interval <= 0.0 {
+            bail!("interval must be positive");
+        }
+        if !self.delay.is_finite() || self.delay < 0.0 {
+            bail!("delay must not be negative");
+        }
+        if self.burst == 0 {
+            bail!("burst must fire at least one volley");
+        }
+        if self.burst > 1 && (!self.burst_interval.is_finite() || self.burst_interval <= 0.0) {
+            bail!("burst interval must be positive");
+        }
+        match self.kind {
+            PatternKind::Spread { count: 0, .. }
+            | PatternKind::Ring { count: 0 }
+            | PatternKind::Spiral { arms: 0, .. } => bail!("pattern fires no bullets"),
+            _ => Ok(()),
+        }
+    }
+
+    /// Bullet velocities of a volley
+    ///
+    /// `aim_angle` is the angle of the volley center (radians, 0.0 is straight down, positive
+    /// angles turn towards +x), `time` the time in s the emitter has been firing.
+    pub fn volley(&self, aim_angle: f32, time: f32) -> Vec<(f32, f32)> {
+        let angles: Vec<f32> = match self.kind {
+            PatternKind::Single => vec![0.0],
+            PatternKind::Spread { count, angle } => {
+                let angle = angle.to_radians();
+                if count == 1 {
+                    vec![0.0]
+                } else {
+                    (0..count)
+                        .map(|i| -angle / 2.0 + angle * i as f32 / (count - 1) as f32)
+                        .collect()
+                }
+            }
+            PatternKind::Ring { count } => Self::ring(count, 0.0),
+            PatternKind::Spiral { arms, rotation } => {
+                Self::ring(arms, (rotation * time).to_radians())
+            }
+        };
+
+        angles
+            .into_iter()
+            .map(|angle| {
+                let angle = aim_angle + angle;
+                (angle.sin() * self.speed, angle.cos() * self.speed)
+            })
+            .collect()
+    }
+
+    fn ring(count: u32, offset: f32) -> Vec<f32> {
+        (0..count)
+            .map(|i| offset + std::f32::consts::TAU * i as f32 / count as f32)
+            .collect()
+    }
+}
+
+/// Timing of an emitter firing a pattern, independent of the game world
+#[derive(Debug, Clone)]
+pub struct EmitterState {
+    /// Time until the next volley
+    cooldown: f32,
+    /// Volleys left in the current burst
+    volleys_left: u32,
+    /// Time since the emitter started firing
+    time: f32,
+}
+
+impl EmitterState {
+    pub fn new(pattern: &BulletPattern) -> Self {
+        Self {
+            cooldown: pattern.delay,
+            volleys_left: pattern.burst,
+            time: 0.0,
+        }
+    }
+
+    /// Advance by `dt` s, returns the velocities of all bullets fired meanwhile
+    ///
+    /// `source` is the position of the emitter, `target` the position of the player (if any).
+    pub fn update(
+        &mut self,
+        pattern: &BulletPattern,
+        dt: f32,
+        source: (f32, f32),
+        target: Option<(f32, f32)>,
+    ) -> Vec<(f32, f32)> {
+        let aim_angle = match (pattern.aim, target) {
+            (Aim::Player, Some(target)) => f32::atan2(target.0 - source.0, target.1 - source.1),
+            _ => 0.0,
+        };
+
+        let mut bullets = Vec::new();
+
+        self.cooldown -= dt;
+        while self.cooldown <= 0.0 {
+            bullets.extend(pattern.volley(aim_angle, self.time));
+
+            self.volleys_left -= 1;
+            if self.volleys_left > 0 {
+                self.cooldown += pattern.burst_interval;
+            } else {
+                self.volleys_left = pattern.burst;
+                self.cooldown += pattern.interval;
+            }
+        }
+
+        self.time += dt;
+
+        bullets
+    }
+}
+
+/// All bullet patterns, by name
+#[derive(Default)]
+pub struct PatternLibrary {
+    patterns: HashMap<String, Arc<BulletPattern>>,
+}
+
+impl PatternLibrary {
+    /// Load patterns from a RON file mapping names to patterns
+    pub fn load<P: AsRef<Path>>(file: P) -> Result<Self> {
+        let file = file.as_ref();
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read pattern file {}", file.display()))?;
+
+        Self::parse(&content).with_context(|| format!("Invalid pattern file {}", file.display()))
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        let definitions: HashMap<String, BulletPattern> = ron::from_str(content)?;
+
+        let patterns = definitions
+            .into_iter()
+            .map(|(name, pattern)| {
+                pattern
+                    .validate()
+                    .with_context(|| format!("invalid pattern \"{}\"", name))?;
+                Ok((name, Arc::new(pattern)))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { patterns })
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<BulletPattern>> {
+        self.patterns.get(name).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    use super::*;
+
+    const SPEED: f32 = 100.0;
+    /// Tolerance of compared velocities in pixels / s
+    const EPSILON: f32 = 1e-3;
+
+    fn pattern(kind: PatternKind) -> BulletPattern {
+        BulletPattern {
+            kind,
+            aim: Aim::Down,
+            speed: SPEED,
+            interval: 1.0,
+            burst: 1,
+            burst_interval: 0.0,
+            delay: 0.0,
+        }
+    }
+
+    /// Angles of the velocities, 0.0 is straight down, positive angles turn towards +x
+    fn angles(velocities: &[(f32, f32)]) -> Vec<f32> {
+        velocities
+            .iter()
+            .map(|&(vx, vy)| f32::atan2(vx, vy))
+            .collect()
+    }
+
+    fn assert_angles(velocities: &[(f32, f32)], expected: &[f32]) {
+        let angles = angles(velocities);
+        assert_eq!(angles.len(), expected.len(), "{:?}", angles);
+        for (angle, expected) in angles.iter().zip(expected) {
+            // Compare on the circle, PI and -PI are the same direction
+            let difference = angle - expected;
+            assert!(
+                difference.sin().atan2(difference.cos()).abs() < EPSILON,
+                "{:?} != {:?}",
+                angles,
+                expected
+            );
+        }
+        for (vx, vy) in velocities {
+            assert!((vx.hypot(*vy) - SPEED).abs() < EPSILON);
+        }
+    }
+
+    /// Number of bullets fired by every update of `dt` over `updates` updates
+    fn fired(pattern: &BulletPattern, dt: f32, updates: usize) -> Vec<usize> {
+        let mut state = EmitterState::new(pattern);
+        (0..updates)
+            .map(|_| state.update(pattern, dt, (0.0, 0.0), None).len())
+            .collect()
+    }
+
+    #[test]
+    fn single_fires_down() {
+        assert_angles(&pattern(PatternKind::Single).volley(0.0, 0.0), &[0.0]);
+    }
+
+    #[test]
+    fn aimed_pattern_fires_at_the_player() {
+        let mut aimed = pattern(PatternKind::Single);
+        aimed.aim = Aim::Player;
+        let mut state = EmitterState::new(&aimed);
+
+        let bullets = state.update(&aimed, 0.1, (100.0, 100.0), Some((200.0, 100.0)));
+        assert_angles(&bullets, &[FRAC_PI_2]);
+
+        // Straight down without a player
+        let bullets = state.update(&aimed, 1.0, (100.0, 100.0), None);
+        assert_angles(&bullets, &[0.0]);
+
+        // Patterns aimed down ignore the player
+        let down = pattern(PatternKind::Single);
+        let bullets = EmitterState::new(&down).update(&down, 0.1, (0.0, 0.0), Some((0.0, -50.0)));
+        assert_angles(&bullets, &[0.0]);
+    }
+
+    #[test]
+    fn spread_is_centered_on_the_aim() {
+        let spread = pattern(PatternKind::Spread {
+            count: 3,
+            angle: 90.0,
+        });
+        assert_angles(&spread.volley(0.0, 0.0), &[-PI / 4.0, 0.0, PI / 4.0]);
+        assert_angles(&spread.volley(PI, 0.0), &[PI * 0.75, PI, PI * 1.25]);
+
+        let single = pattern(PatternKind::Spread {
+            count: 1,
+            angle: 90.0,
+        });
+        assert_angles(&single.volley(0.0, 0.0), &[0.0]);
+    }
+
+    #[test]
+    fn ring_covers_the_full_circle() {
+        let ring = pattern(PatternKind::Ring { count: 4 });
+        assert_angles(&ring.volley(0.0, 0.0), &[0.0, FRAC_PI_2, PI, PI * 1.5]);
+        // Time doesn't turn rings
+        assert_angles(&ring.volley(0.0, 3.0), &[0.0, FRAC_PI_2, PI, PI * 1.5]);
+    }
+
+    #[test]
+    fn spiral_rotates_over_time() {
+        let spiral = pattern(PatternKind::Spiral {
+            arms: 2,
+            rotation: 90.0,
+        });
+        assert_angles(&spiral.volley(0.0, 0.0), &[0.0, PI]);
+        assert_angles(&spiral.volley(0.0, 0.5), &[PI / 4.0, PI * 1.25]);
+        assert_angles(&spiral.volley(0.0, 1.0), &[FRAC_PI_2, PI * 1.5]);
+    }
+
+    #[test]
+    fn first_volley_waits_for_the_delay() {
+        let mut delayed = pattern(PatternKind::Single);
+        delayed.delay = 0.5;
+        assert_eq!(fired(&delayed, 0.25, 8), [0, 1, 0, 0, 0, 1, 0, 0]);
+    }
+
+    #[test]
+    fn rejects_invalid_delays() {
+        let mut delayed = pattern(PatternKind::Single);
+        delayed.delay = 0.0;
+        assert!(delayed.validate().is_ok());
+        for delay in [-0.5, f32::NAN, f32::INFINITY] {
+            delayed.delay = delay;
+            assert!(delayed.validate().is_err(), "delay {}", delay);
+        }
+    }
+
+    #[test]
+    fn bursts_fire_volleys_at_the_burst_interval() {
+        let mut burst = pattern(PatternKind::Single);
+        burst.burst = 3;
+        burst.burst_interval = 0.25;
+        burst.interval = 1.0;
+
+        // Volleys at 0, 0.25, 0.5, then the next burst after the interval at 1.5, every update
+        // fires the volleys due until its end
+        assert_eq!(fired(&burst, 0.25, 9), [2, 1, 0, 0, 0, 1, 1, 1, 0]);
+    }
+
+    #[test]
+    fn timing_does_not_depend_on_the_tick_rate() {
+        let mut burst = pattern(PatternKind::Ring { count: 2 });
+        burst.burst = 2;
+        burst.burst_interval = 0.125;
+        burst.interval = 0.5;
+
+        let total = |dt: f32, updates: usize| fired(&burst, dt, updates).iter().sum::<usize>();
+        assert_eq!(total(0.5, 4), total(0.125, 16));
+        assert_eq!(total(0.5, 4), total(1.0 / 64.0, 128));
+        // All volleys of a long update, at 0, 0.125, 0.625, 0.75, 1.25, 1.375, 1.875 and 2.0
+        assert_eq!(fired(&burst, 2.0, 1), [16]);
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        let parse = |fields: &str| {
+            PatternLibrary::parse(&format!(r#"{{ "test": (kind: Single, {}) }}"#, fields))
+        };
+
+        assert!(parse("speed: 100.0, interval: 1.0").is_ok());
+        assert!(parse("speed: -100.0, interval: 1.0").is_err());
+        assert!(parse("speed: NaN, interval: 1.0").is_err());
+        assert!(parse("speed: 100.0, interval: 0.0").is_err());
+        assert!(parse("speed: 100.0, interval: NaN").is_err());
+        assert!(parse("speed: 100.0, interval: 1.0, delay: 0.5").is_ok());
+        assert!(parse("speed: 100.0, interval: 1.0, delay: -0.5").is_err());
+        assert!(parse("speed: 100.0, interval: 1.0, delay: inf").is_err());
+        assert!(parse("speed: 100.0, interval: 1.0, burst: 0").is_err());
+        assert!(parse("speed: 100.0, interval: 1.0, burst: 2").is_err());
+        assert!(PatternLibrary::parse(
+            r#"{ "test": (kind: Ring(count: 0), speed: 100.0, interval: 1.0) }"#
+        )
+        .is_err());
+    }
+}
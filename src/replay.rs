@@ -0,0 +1,302 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use anyhow::{bail, Context, Result};
+use log::warn;
+
+use crate::{headless::InputSource, resource::player_input::PlayerInput, FRAME_RATE_GAME};
+
+const MAGIC: &[u8; 4] = b"DRRP";
+const FORMAT_VERSION: u8 = 1;
+
+/// Longest replay accepted when reading, 10 hours at the game's tick rate, so corrupt run
+/// lengths can't exhaust the memory
+const MAX_TICKS: usize = FRAME_RATE_GAME as usize * 60 * 60 * 10;
+
+/// Player input of every physics tick of a play session
+///
+/// File format (little endian):
+/// - magic `DRRP`, format version (u8)
+/// - game version: length (u8), UTF-8 string
+/// - physics tick rate in Hz (u32)
+/// - inputs, run-length encoded: input bits (u8), number of ticks (u32), until end of file
+pub struct Replay {
+    game_version: String,
+    tick_rate: u32,
+    inputs: Vec<PlayerInput>,
+}
+
+impl Default for Replay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Replay {
+    /// Empty replay for the running game version and tick rate
+    pub fn new() -> Self {
+        Self {
+            game_version: env!("CARGO_PKG_VERSION").to_string(),
+            tick_rate: FRAME_RATE_GAME,
+            inputs: Vec::new(),
+        }
+    }
+
+    /// Append the input of the next physics tick
+    pub fn record(&mut self, input: PlayerInput) {
+        self.inputs.push(input);
+    }
+
+    pub fn game_version(&self) -> &str {
+        &self.game_version
+    }
+
+    pub fn tick_rate(&self) -> u32 {
+        self.tick_rate
+    }
+
+    /// Number of recorded physics ticks
+    pub fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create replay file {}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+
+        self.write(&mut writer)
+            .and_then(|_| Ok(writer.flush()?))
+            .with_context(|| format!("Failed to write replay file {}", path.display()))
+    }
+
+    /// Load a replay, fails if it was recorded with a different physics tick rate
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .with_context(|| format!("Failed to open replay file {}", path.display()))?;
+
+        let replay = Self::read(&mut BufReader::new(file))
+            .with_context(|| format!("Invalid replay file {}", path.display()))?;
+
+        if replay.tick_rate != FRAME_RATE_GAME {
+            bail!(
+                "Replay {} was recorded at {} Hz, game is running at {} Hz",
+                path.display(),
+                replay.tick_rate,
+                FRAME_RATE_GAME
+            );
+        }
+
+        if replay.game_version != env!("CARGO_PKG_VERSION") {
+            warn!(target: "Replay",
+                "Replay {} was recorded with game version {}, playback might diverge",
+                path.display(),
+                replay.game_version
+            );
+        }
+
+        Ok(replay)
+    }
+
+    fn write<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[FORMAT_VERSION])?;
+
+        let version = self.game_version.as_bytes();
+        writer.write_all(&[u8::try_from(version.len()).context("game version too long")?])?;
+        writer.write_all(version)?;
+
+        writer.write_all(&self.tick_rate.to_le_bytes())?;
+
+        let mut inputs = self.inputs.iter().peekable();
+        while let Some(input) = inputs.next() {
+            let mut run_length: u32 = 1;
+            while run_length < u32::MAX && inputs.next_if_eq(&input).is_some() {
+                run_length += 1;
+            }
+
+            writer.write_all(&[input.to_bits()])?;
+            writer.write_all(&run_length.to_le_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    fn read<R: Read>(reader: &mut R) -> Result<Self> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            bail!("not a replay file");
+        }
+
+        let mut byte = [0u8; 1];
+        reader.read_exact(&mut byte)?;
+        if byte[0] != FORMAT_VERSION {
+            bail!("unsupported replay format version {}", byte[0]);
+        }
+
+        reader.read_exact(&mut byte)?;
+        let mut version = vec![0u8; byte[0] as usize];
+        reader.read_exact(&mut version)?;
+        let game_version = String::from_utf8(version).context("game version is not UTF-8")?;
+
+        let mut word = [0u8; 4];
+        reader.read_exact(&mut word)?;
+        let tick_rate = u32::from_le_bytes(word);
+
+        let mut inputs = Vec::new();
+        loop {
+            match reader.read(&mut byte)? {
+                0 => break,
+                _ => {
+                    let input = PlayerInput::from_bits(byte[0])
+                        .with_context(|| format!("invalid input at tick {}", inputs.len()))?;
+                    reader.read_exact(&mut word)?;
+                    let run_length = u32::from_le_bytes(word) as usize;
+                    let ticks = inputs.len() + run_length;
+                    if ticks > MAX_TICKS {
+                        bail!("replay is longer than {} ticks", MAX_TICKS);
+                    }
+                    inputs.resize(ticks, input);
+                }
+            }
+        }
+
+        Ok(Self {
+            game_version,
+            tick_rate,
+            inputs,
+        })
+    }
+}
+
+impl InputSource for Replay {
+    /// Recorded input of the given tick, no input once the replay has ended
+    fn input_at(&self, tick: u64) -> PlayerInput {
+        self.inputs.get(tick as usize).copied().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::PathBuf};
+
+    use super::*;
+
+    /// File in the temporary directory, removed when dropped
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str) -> Self {
+            Self(env::temp_dir().join(format!("deimosreborn-{}-{}", std::process::id(), name)))
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    fn input(bits: u8) -> PlayerInput {
+        PlayerInput::from_bits(bits).unwrap()
+    }
+
+    fn recorded() -> Replay {
+        let mut replay = Replay::new();
+        for bits in [0, 0, 0b10001, 0b10001, 0b00010, 0, 0b11111] {
+            replay.record(input(bits));
+        }
+        replay
+    }
+
+    fn encode(replay: &Replay) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        replay.write(&mut bytes).unwrap();
+        bytes
+    }
+
+    fn error_message(bytes: &[u8]) -> String {
+        match Replay::read(&mut &bytes[..]) {
+            Ok(_) => panic!("replay should be rejected"),
+            Err(e) => format!("{:#}", e),
+        }
+    }
+
+    #[test]
+    fn saved_replay_loads_the_same_inputs() {
+        let file = TempFile::new("round_trip.drrp");
+        let replay = recorded();
+        replay.save(&file.0).unwrap();
+
+        let loaded = Replay::load(&file.0).unwrap();
+        assert_eq!(loaded.game_version(), env!("CARGO_PKG_VERSION"));
+        assert_eq!(loaded.tick_rate(), FRAME_RATE_GAME);
+        assert_eq!(loaded.inputs, replay.inputs);
+        // No input after the end
+        assert_eq!(loaded.input_at(7), PlayerInput::default());
+    }
+
+    #[test]
+    fn inputs_are_run_length_encoded() {
+        let header = 4 + 1 + 1 + env!("CARGO_PKG_VERSION").len() + 4;
+        // 5 runs of 5 bytes each
+        assert_eq!(encode(&recorded()).len(), header + 5 * 5);
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        let mut bytes = encode(&recorded());
+        bytes[0] = b'X';
+        assert!(error_message(&bytes).contains("not a replay file"));
+    }
+
+    #[test]
+    fn rejects_unknown_format_versions() {
+        let mut bytes = encode(&recorded());
+        bytes[4] = FORMAT_VERSION + 1;
+        assert!(error_message(&bytes).contains("unsupported replay format version"));
+    }
+
+    #[test]
+    fn rejects_other_tick_rates() {
+        let file = TempFile::new("tick_rate.drrp");
+        let mut replay = recorded();
+        replay.tick_rate = FRAME_RATE_GAME * 2;
+        replay.save(&file.0).unwrap();
+
+        let message = format!("{:#}", Replay::load(&file.0).err().unwrap());
+        assert!(message.contains("was recorded at"), "{}", message);
+    }
+
+    #[test]
+    fn rejects_overlong_runs() {
+        let mut bytes = encode(&Replay::new());
+        bytes.push(0);
+        bytes.extend(u32::MAX.to_le_bytes());
+        assert!(error_message(&bytes).contains("replay is longer than"));
+
+        // Runs adding up beyond the limit
+        let mut bytes = encode(&Replay::new());
+        for _ in 0..2 {
+            bytes.push(0);
+            bytes.extend((MAX_TICKS as u32 / 2 + 1).to_le_bytes());
+        }
+        assert!(error_message(&bytes).contains("replay is longer than"));
+    }
+
+    #[test]
+    fn rejects_truncated_runs() {
+        let bytes = encode(&recorded());
+        assert!(Replay::read(&mut &bytes[..bytes.len() - 2]).is_err());
+    }
+}
@@ -0,0 +1,77 @@
+use crate::sprite::SpriteId;
+
+/// Scrolling plane of tiles taken from the frames of a sprite sheet
+pub struct BackgroundPlane {
+    pub sprite: SpriteId,
+    /// Width and height of a tile in pixels
+    pub tile_size: (usize, usize),
+    /// Frame index of every tile, top row first, `None` leaves the tile transparent
+    pub tiles: Vec<Vec<Option<usize>>>,
+    /// Factor applied to the level scroll speed, planes further away scroll slower
+    pub parallax: f32,
+    /// Start over with the bottom row once the top row has scrolled into view
+    pub repeat: bool,
+    /// Distance scrolled in pixels, not wrapped
+    offset: f32,
+    previous_offset: f32,
+}
+
+impl BackgroundPlane {
+    pub fn new(
+        sprite: SpriteId,
+        tile_size: (usize, usize),
+        tiles: Vec<Vec<Option<usize>>>,
+        parallax: f32,
+        repeat: bool,
+    ) -> Self {
+        Self {
+            sprite,
+            tile_size,
+            tiles,
+            parallax,
+            repeat,
+            offset: 0.0,
+            previous_offset: 0.0,
+        }
+    }
+
+    /// Height of the whole map in pixels
+    pub fn height(&self) -> usize {
+        self.tiles.len() * self.tile_size.1
+    }
+
+    /// Scroll by `distance` pixels of the level, scaled by the parallax factor
+    pub fn scroll(&mut self, distance: f32) {
+        self.previous_offset = self.offset;
+        self.offset += distance * self.parallax;
+    }
+
+    /// Offset between the last two ticks, see `Timing` for `alpha`
+    pub fn interpolated_offset(&self, alpha: f32) -> f32 {
+        self.offset * alpha + self.previous_offset * (1.0 - alpha)
+    }
+}
+
+/// Terrain of the current stage, rendered below all sprites
+pub struct Background {
+    pub color: (u8, u8, u8),
+    /// Planes in render order, the first one is the furthest away
+    pub planes: Vec<BackgroundPlane>,
+}
+
+impl Background {
+    pub fn scroll(&mut self, distance: f32) {
+        for plane in &mut self.planes {
+            plane.scroll(distance);
+        }
+    }
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Self {
+            color: (0, 100, 200),
+            planes: Vec::new(),
+        }
+    }
+}
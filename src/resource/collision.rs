@@ -0,0 +1,28 @@
+use specs::Entity;
+
+/// `entity` was hit by `other` (i.e. the mask of `entity` contains the layer of `other`)
+#[derive(Debug, Copy, Clone)]
+pub struct CollisionEvent {
+    pub entity: Entity,
+    pub other: Entity,
+}
+
+/// Collisions detected during the current physics tick
+#[derive(Default)]
+pub struct Collisions {
+    events: Vec<CollisionEvent>,
+}
+
+impl Collisions {
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    pub fn push(&mut self, event: CollisionEvent) {
+        self.events.push(event);
+    }
+
+    pub fn events(&self) -> &[CollisionEvent] {
+        &self.events
+    }
+}
@@ -0,0 +1,77 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// Number of frames shown by the frame time graph
+pub const FRAME_HISTORY: usize = 120;
+
+/// Period the frame rate and physics tick rate are measured over
+const RATE_PERIOD: Duration = Duration::from_secs(1);
+
+/// Timing statistics of the main loop and entity counts, shown by the render system when enabled
+#[derive(Default)]
+pub struct DebugOverlay {
+    pub enabled: bool,
+    /// Time between the starts of two frames, oldest first
+    frame_times: VecDeque<Duration>,
+    /// Start of all frames within the last `RATE_PERIOD`
+    frames: VecDeque<Instant>,
+    /// All physics ticks within the last `RATE_PERIOD`
+    ticks: VecDeque<Instant>,
+    /// Physics updates skipped because the game fell too far behind
+    pub missed_updates: u64,
+    /// Number of entities and number of components per storage, only updated while enabled
+    pub entity_counts: Vec<(&'static str, usize)>,
+}
+
+impl DebugOverlay {
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+    }
+
+    pub fn record_frame(&mut self, start: Instant) {
+        if let Some(&previous) = self.frames.back() {
+            if self.frame_times.len() == FRAME_HISTORY {
+                self.frame_times.pop_front();
+            }
+            self.frame_times.push_back(start - previous);
+        }
+
+        self.frames.push_back(start);
+        Self::expire(&mut self.frames, start);
+    }
+
+    pub fn record_tick(&mut self, tick: Instant) {
+        self.ticks.push_back(tick);
+        Self::expire(&mut self.ticks, tick);
+    }
+
+    pub fn record_missed_update(&mut self) {
+        self.missed_updates += 1;
+    }
+
+    /// Frames rendered within the last second
+    pub fn frame_rate(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Physics ticks run within the last second
+    pub fn tick_rate(&self) -> usize {
+        self.ticks.len()
+    }
+
+    /// Time between the starts of the last frames, oldest first
+    pub fn frame_times(&self) -> impl Iterator<Item = Duration> + '_ {
+        self.frame_times.iter().copied()
+    }
+
+    fn expire(instants: &mut VecDeque<Instant>, now: Instant) {
+        while let Some(&oldest) = instants.front() {
+            if now - oldest < RATE_PERIOD {
+                break;
+            }
+            instants.pop_front();
+        }
+    }
+}
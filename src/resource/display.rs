@@ -0,0 +1,134 @@
+use std::{fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+use sdl2::rect::Rect;
+use serde::Deserialize;
+
+use crate::{GAME_HEIGHT, GAME_WIDTH};
+
+/// Largest integer factor the window size can be set to
+pub const MAX_WINDOW_SCALE: u32 = 4;
+
+/// How the game resolution is scaled to the window
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Deserialize)]
+pub enum Scaling {
+    /// Largest integer factor fitting the window, sharp pixels
+    #[default]
+    Integer,
+    /// Largest factor fitting the window while keeping the aspect ratio
+    Aspect,
+}
+
+impl Scaling {
+    /// Part of a window of the given size the game is shown in, centered with letterbox bars
+    /// around it
+    pub fn viewport(self, window_size: (u32, u32)) -> Rect {
+        let (window_width, window_height) = window_size;
+        let scale = (window_width as f32 / GAME_WIDTH as f32)
+            .min(window_height as f32 / GAME_HEIGHT as f32);
+        // Integer scaling falls back to aspect scaling for windows smaller than the game
+        let scale = match self {
+            Scaling::Integer if scale >= 1.0 => scale.floor(),
+            _ => scale,
+        };
+
+        let width = ((GAME_WIDTH as f32 * scale).round() as u32).max(1);
+        let height = ((GAME_HEIGHT as f32 * scale).round() as u32).max(1);
+
+        Rect::new(
+            (window_width as i32 - width as i32) / 2,
+            (window_height as i32 - height as i32) / 2,
+            width,
+            height,
+        )
+    }
+}
+
+/// Whether the game is shown in a window or covers the whole screen
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Deserialize)]
+pub enum WindowMode {
+    #[default]
+    Windowed,
+    /// Fullscreen in the resolution of the desktop
+    DesktopFullscreen,
+    /// Fullscreen changing the display resolution to the window size
+    Fullscreen,
+}
+
+/// Presentation of the game in the window as written in the display settings file, changes
+/// are applied by the render system
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Display {
+    pub scaling: Scaling,
+    pub mode: WindowMode,
+    /// Window size as a multiple of the game resolution, from 1 to `MAX_WINDOW_SCALE`
+    pub window_scale: u32,
+    /// Wait for the vertical blank of the display before presenting a frame
+    pub vsync: bool,
+}
+
+impl Default for Display {
+    fn default() -> Self {
+        Self {
+            scaling: Scaling::default(),
+            mode: WindowMode::default(),
+            window_scale: 2,
+            vsync: true,
+        }
+    }
+}
+
+impl Display {
+    pub fn load<P: AsRef<Path>>(file: P) -> Result<Self> {
+        let file = file.as_ref();
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read display settings {}", file.display()))?;
+
+        Self::parse(&content)
+            .with_context(|| format!("Invalid display settings {}", file.display()))
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        let display: Self = ron::from_str(content)?;
+        if !(1..=MAX_WINDOW_SCALE).contains(&display.window_scale) {
+            bail!("window_scale must be between 1 and {}", MAX_WINDOW_SCALE);
+        }
+
+        Ok(display)
+    }
+
+    /// Size of the window when not in fullscreen
+    pub fn window_size(&self) -> (u32, u32) {
+        (
+            GAME_WIDTH * self.window_scale,
+            GAME_HEIGHT * self.window_scale,
+        )
+    }
+
+    /// Switch between a window and desktop fullscreen
+    pub fn toggle_fullscreen(&mut self) {
+        self.mode = match self.mode {
+            WindowMode::Windowed => WindowMode::DesktopFullscreen,
+            WindowMode::DesktopFullscreen | WindowMode::Fullscreen => WindowMode::Windowed,
+        };
+    }
+
+    /// Go through windowed, desktop fullscreen and fullscreen mode
+    pub fn cycle_mode(&mut self) {
+        self.mode = match self.mode {
+            WindowMode::Windowed => WindowMode::DesktopFullscreen,
+            WindowMode::DesktopFullscreen => WindowMode::Fullscreen,
+            WindowMode::Fullscreen => WindowMode::Windowed,
+        };
+    }
+
+    /// Go through the window scales from 1 to `MAX_WINDOW_SCALE`
+    pub fn cycle_window_scale(&mut self) {
+        self.window_scale = self.window_scale % MAX_WINDOW_SCALE + 1;
+    }
+
+    pub fn toggle_vsync(&mut self) {
+        self.vsync = !self.vsync;
+    }
+}
@@ -0,0 +1,26 @@
+/// Lives the player starts with
+const STARTING_LIVES: u32 = 3;
+
+/// Bombs the player starts with
+const STARTING_BOMBS: u32 = 3;
+
+/// Progress of the player, as shown by the HUD
+pub struct GameState {
+    pub score: u64,
+    /// Remaining lives, including the current one
+    pub lives: u32,
+    pub bombs: u32,
+    /// Power level of the main weapon, starting at 1
+    pub weapon_level: u32,
+}
+
+impl Default for GameState {
+    fn default() -> Self {
+        Self {
+            score: 0,
+            lives: STARTING_LIVES,
+            bombs: STARTING_BOMBS,
+            weapon_level: 1,
+        }
+    }
+}
@@ -1,3 +1,11 @@
+pub mod background;
+pub mod collision;
+pub mod debug;
+pub mod display;
+pub mod game_state;
 pub mod player_input;
+pub mod playfield;
+pub mod screenshot;
 pub mod sound;
+pub mod spatial_hash;
 pub mod timing;
@@ -1,6 +1,6 @@
 use sdl2::{event::Event, keyboard::Keycode};
 
-#[derive(Default)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct PlayerInput {
     pub left: bool,
     pub right: bool,
@@ -10,6 +10,44 @@ pub struct PlayerInput {
 }
 
 impl PlayerInput {
+    const LEFT: u8 = 1 << 0;
+    const RIGHT: u8 = 1 << 1;
+    const UP: u8 = 1 << 2;
+    const DOWN: u8 = 1 << 3;
+    const SHOOT_AIR: u8 = 1 << 4;
+
+    /// Compact representation, one bit per input
+    pub fn to_bits(self) -> u8 {
+        let mut bits = 0;
+        for (pressed, bit) in [
+            (self.left, Self::LEFT),
+            (self.right, Self::RIGHT),
+            (self.up, Self::UP),
+            (self.down, Self::DOWN),
+            (self.shoot_air, Self::SHOOT_AIR),
+        ] {
+            if pressed {
+                bits |= bit;
+            }
+        }
+        bits
+    }
+
+    /// Inverse of `to_bits`, `None` if unknown bits are set
+    pub fn from_bits(bits: u8) -> Option<Self> {
+        if bits & !(Self::LEFT | Self::RIGHT | Self::UP | Self::DOWN | Self::SHOOT_AIR) != 0 {
+            return None;
+        }
+
+        Some(Self {
+            left: bits & Self::LEFT != 0,
+            right: bits & Self::RIGHT != 0,
+            up: bits & Self::UP != 0,
+            down: bits & Self::DOWN != 0,
+            shoot_air: bits & Self::SHOOT_AIR != 0,
+        })
+    }
+
     pub fn update_player_input(&mut self, event: Event) {
         match event {
             Event::KeyDown {
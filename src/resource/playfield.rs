@@ -0,0 +1,11 @@
+/// Area the game is played in, in game coordinates with the origin in the top left corner
+pub struct Playfield {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Playfield {
+    pub fn new(width: f32, height: f32) -> Self {
+        Self { width, height }
+    }
+}
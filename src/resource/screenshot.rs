@@ -0,0 +1,87 @@
+use std::{
+    fs, mem,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use sdl2::{image::SaveSurface, pixels::PixelFormatEnum, surface::Surface};
+
+use crate::errors::SdlError;
+
+/// Directory timestamped screenshots are written to
+pub const SCREENSHOT_DIR: &str = "screenshots";
+
+/// Size a screenshot is captured in
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Resolution {
+    /// The whole window including letterbox bars and HUD, as presented
+    Window,
+    /// The game world in `GAME_WIDTH`x`GAME_HEIGHT`, with the HUD laid out as in a window of
+    /// that size
+    Native,
+}
+
+/// Screenshot to be captured when the next frame is rendered
+#[derive(Debug, Clone)]
+pub struct ScreenshotRequest {
+    pub path: PathBuf,
+    pub resolution: Resolution,
+}
+
+/// Screenshots requested for the next frame and the outcome of the ones captured already
+#[derive(Default)]
+pub struct Screenshots {
+    pending: Vec<ScreenshotRequest>,
+    finished: Vec<(PathBuf, Result<()>)>,
+}
+
+impl Screenshots {
+    /// Capture the next frame to a timestamped PNG file in `SCREENSHOT_DIR`
+    pub fn request(&mut self, resolution: Resolution) -> PathBuf {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let path = Path::new(SCREENSHOT_DIR).join(format!("screenshot_{}.png", timestamp));
+
+        self.request_to(path.clone(), resolution);
+        path
+    }
+
+    /// Capture the next frame to the given PNG file
+    pub fn request_to<P: Into<PathBuf>>(&mut self, path: P, resolution: Resolution) {
+        self.pending.push(ScreenshotRequest {
+            path: path.into(),
+            resolution,
+        });
+    }
+
+    pub fn take_pending(&mut self) -> Vec<ScreenshotRequest> {
+        mem::take(&mut self.pending)
+    }
+
+    pub fn finish(&mut self, path: PathBuf, result: Result<()>) {
+        self.finished.push((path, result));
+    }
+
+    /// Outcome of all screenshots captured since the last call
+    pub fn take_finished(&mut self) -> Vec<(PathBuf, Result<()>)> {
+        mem::take(&mut self.finished)
+    }
+}
+
+/// Write RGB24 pixel data as PNG file, creating missing directories
+pub fn save_png(path: &Path, pixels: &mut [u8], width: u32, height: u32) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory {}", dir.display()))?;
+    }
+
+    let surface = Surface::from_data(pixels, width, height, width * 3, PixelFormatEnum::RGB24)
+        .map_err(SdlError::ScreenshotError)?;
+    surface
+        .save(path)
+        .map_err(SdlError::ScreenshotError)
+        .with_context(|| format!("Failed to save screenshot {}", path.display()))
+}
@@ -2,23 +2,39 @@ use std::sync::{mpsc::Sender, Mutex};
 
 use crate::sound::SoundId;
 
+/// Audio output requested by the game systems
+#[derive(Debug, Clone)]
+pub enum AudioRequest {
+    Sound(SoundId),
+    /// Start looping the music in the given file, replacing the current music
+    Music(String),
+}
+
 pub struct AudioInterface {
     // TODO: this is a workaround around `Chunks` not being Sync+Send, maybe there is a better way?
-    sender: Mutex<Sender<SoundId>>,
+    sender: Mutex<Sender<AudioRequest>>,
 }
 
 impl AudioInterface {
-    pub fn new(sender: Sender<SoundId>) -> Self {
+    pub fn new(sender: Sender<AudioRequest>) -> Self {
         Self {
             sender: Mutex::new(sender),
         }
     }
 
     pub fn play_sound(&self, sound: SoundId) {
+        self.send(AudioRequest::Sound(sound));
+    }
+
+    pub fn play_music(&self, file: &str) {
+        self.send(AudioRequest::Music(file.to_string()));
+    }
+
+    fn send(&self, request: AudioRequest) {
         self.sender
             .lock()
             .expect("mutex should be valid")
-            .send(sound)
+            .send(request)
             .expect("channel should be valid");
     }
 }
@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+
+use specs::Entity;
+
+use crate::component::hitbox::CollisionLayers;
+
+/// Default edge length of a grid cell in pixels
+const DEFAULT_CELL_SIZE: f32 = 64.0;
+
+#[derive(Debug, Copy, Clone)]
+pub struct SpatialEntry {
+    pub entity: Entity,
+    pub layer: CollisionLayers,
+    /// Bounding box (x_min, y_min, x_max, y_max)
+    pub bounds: (f32, f32, f32, f32),
+}
+
+impl SpatialEntry {
+    pub fn center(&self) -> (f32, f32) {
+        (
+            (self.bounds.0 + self.bounds.2) / 2.0,
+            (self.bounds.1 + self.bounds.3) / 2.0,
+        )
+    }
+
+    fn overlaps(&self, bounds: (f32, f32, f32, f32)) -> bool {
+        self.bounds.0 <= bounds.2
+            && bounds.0 <= self.bounds.2
+            && self.bounds.1 <= bounds.3
+            && bounds.1 <= self.bounds.3
+    }
+}
+
+/// Uniform grid of all entities with a hitbox, rebuilt every physics tick
+pub struct SpatialHash {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<usize>>,
+    entries: Vec<SpatialEntry>,
+    /// Range of occupied cells (x_min, y_min, x_max, y_max)
+    extent: Option<(i32, i32, i32, i32)>,
+}
+
+impl Default for SpatialHash {
+    fn default() -> Self {
+        Self::new(DEFAULT_CELL_SIZE)
+    }
+}
+
+impl SpatialHash {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            entries: Vec::new(),
+            extent: None,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        // Keep the allocated cells around, the next tick will most likely need them again
+        for cell in self.cells.values_mut() {
+            cell.clear();
+        }
+        self.entries.clear();
+        self.extent = None;
+    }
+
+    pub fn insert(&mut self, entity: Entity, layer: CollisionLayers, bounds: (f32, f32, f32, f32)) {
+        let idx = self.entries.len();
+        self.entries.push(SpatialEntry {
+            entity,
+            layer,
+            bounds,
+        });
+
+        let (cx_min, cy_min, cx_max, cy_max) = self.cell_range(bounds);
+        for cy in cy_min..=cy_max {
+            for cx in cx_min..=cx_max {
+                self.cells.entry((cx, cy)).or_default().push(idx);
+            }
+        }
+
+        self.extent = Some(match self.extent {
+            None => (cx_min, cy_min, cx_max, cy_max),
+            Some((x_min, y_min, x_max, y_max)) => (
+                x_min.min(cx_min),
+                y_min.min(cy_min),
+                x_max.max(cx_max),
+                y_max.max(cy_max),
+            ),
+        });
+    }
+
+    /// All entries, in insertion order
+    pub fn entries(&self) -> &[SpatialEntry] {
+        &self.entries
+    }
+
+    /// Entries whose bounding box overlaps the given region
+    pub fn query_region(
+        &self,
+        x_min: f32,
+        y_min: f32,
+        x_max: f32,
+        y_max: f32,
+    ) -> Vec<&SpatialEntry> {
+        let region = (x_min, y_min, x_max, y_max);
+
+        let mut indices = Vec::new();
+        let (cx_min, cy_min, cx_max, cy_max) = self.cell_range(region);
+        for cy in cy_min..=cy_max {
+            for cx in cx_min..=cx_max {
+                if let Some(cell) = self.cells.get(&(cx, cy)) {
+                    indices.extend(
+                        cell.iter()
+                            .copied()
+                            .filter(|idx| self.entries[*idx].overlaps(region)),
+                    );
+                }
+            }
+        }
+
+        // Entries spanning multiple cells are found more than once
+        indices.sort_unstable();
+        indices.dedup();
+
+        indices.into_iter().map(|idx| &self.entries[idx]).collect()
+    }
+
+    /// Entry closest to (x, y) (measured between centers) that passes `filter`
+    pub fn nearest<F>(&self, x: f32, y: f32, filter: F) -> Option<&SpatialEntry>
+    where
+        F: Fn(&SpatialEntry) -> bool,
+    {
+        let (x_min, y_min, x_max, y_max) = self.extent?;
+        let (px, py) = self.cell_of(x, y);
+
+        // Search rings of cells around the point until no closer entry is possible
+        let max_ring = [px - x_min, x_max - px, py - y_min, y_max - py]
+            .into_iter()
+            .max()
+            .unwrap_or(0)
+            .max(0);
+
+        let mut best: Option<(f32, usize)> = None;
+        for ring in 0..=max_ring {
+            if let Some((distance_sq, _)) = best {
+                // Lower bound for the distance to any entry in this ring
+                let ring_distance = (ring - 1).max(0) as f32 * self.cell_size;
+                if ring_distance * ring_distance > distance_sq {
+                    break;
+                }
+            }
+
+            for cy in py - ring..=py + ring {
+                for cx in px - ring..=px + ring {
+                    if (cx - px).abs() != ring && (cy - py).abs() != ring {
+                        continue;
+                    }
+
+                    for &idx in self.cells.get(&(cx, cy)).into_iter().flatten() {
+                        let entry = &self.entries[idx];
+                        if !filter(entry) {
+                            continue;
+                        }
+
+                        let (ex, ey) = entry.center();
+                        let distance_sq = (ex - x) * (ex - x) + (ey - y) * (ey - y);
+                        if best.is_none_or(|(best_sq, best_idx)| {
+                            distance_sq < best_sq || (distance_sq == best_sq && idx < best_idx)
+                        }) {
+                            best = Some((distance_sq, idx));
+                        }
+                    }
+                }
+            }
+        }
+
+        best.map(|(_, idx)| &self.entries[idx])
+    }
+
+    /// Pairs of entry indices with overlapping bounding boxes, each pair reported once
+    ///
+    /// The order only depends on the insertion order, which keeps the simulation deterministic.
+    pub fn overlapping_pairs(&self) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+
+        for (i, entry) in self.entries.iter().enumerate() {
+            let (cx_min, cy_min, cx_max, cy_max) = self.cell_range(entry.bounds);
+            for cy in cy_min..=cy_max {
+                for cx in cx_min..=cx_max {
+                    for &j in self.cells.get(&(cx, cy)).into_iter().flatten() {
+                        if j <= i || !entry.overlaps(self.entries[j].bounds) {
+                            continue;
+                        }
+
+                        // Only report the pair in the cell containing the corner of the overlap
+                        let other = &self.entries[j];
+                        let corner = self.cell_of(
+                            entry.bounds.0.max(other.bounds.0),
+                            entry.bounds.1.max(other.bounds.1),
+                        );
+                        if corner == (cx, cy) {
+                            pairs.push((i, j));
+                        }
+                    }
+                }
+            }
+        }
+
+        pairs
+    }
+
+    fn cell_of(&self, x: f32, y: f32) -> (i32, i32) {
+        (
+            (x / self.cell_size).floor() as i32,
+            (y / self.cell_size).floor() as i32,
+        )
+    }
+
+    fn cell_range(&self, bounds: (f32, f32, f32, f32)) -> (i32, i32, i32, i32) {
+        let (cx_min, cy_min) = self.cell_of(bounds.0, bounds.1);
+        let (cx_max, cy_max) = self.cell_of(bounds.2, bounds.3);
+        (cx_min, cy_min, cx_max, cy_max)
+    }
+}
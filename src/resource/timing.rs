@@ -1,5 +1,17 @@
 use std::time::{Duration, Instant};
 
+/// Remaining times this close to 0 count as elapsed, as tick durations aren't exact in f32
+pub const TIME_TOLERANCE: f32 = 1e-6;
+
+/// Time spent beyond an elapsed `remaining` time (as a negative time), 0.0 within the tolerance
+pub fn overshoot(remaining: f32) -> f32 {
+    if remaining < -TIME_TOLERANCE {
+        remaining
+    } else {
+        0.0
+    }
+}
+
 pub struct Timing {
     pub physics_tick: Instant,
     pub next_vsync: Option<Instant>,
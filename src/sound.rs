@@ -3,8 +3,9 @@ use sdl2::mixer::Chunk;
 #[derive(Debug, Copy, Clone)]
 pub struct SoundId(usize);
 
+#[derive(Default)]
 pub struct SoundLibrary {
-    sounds: Vec<Chunk>,
+    sounds: Vec<Option<Chunk>>,
 }
 
 impl SoundLibrary {
@@ -13,11 +14,17 @@ impl SoundLibrary {
     }
 
     pub fn insert(&mut self, sound: Chunk) -> SoundId {
-        self.sounds.push(sound);
+        self.sounds.push(Some(sound));
         SoundId(self.sounds.len() - 1)
     }
 
-    pub fn get(&self, id: SoundId) -> &Chunk {
-        &self.sounds[id.0]
+    /// Reserve a sound id without any sample data (used when running without audio)
+    pub fn insert_silent(&mut self) -> SoundId {
+        self.sounds.push(None);
+        SoundId(self.sounds.len() - 1)
+    }
+
+    pub fn get(&self, id: SoundId) -> Option<&Chunk> {
+        self.sounds[id.0].as_ref()
     }
 }
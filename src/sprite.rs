@@ -1,4 +1,5 @@
 use std::path::Path;
+use std::sync::Arc;
 
 use sdl2::gfx::primitives::DrawRenderer;
 use sdl2::image::ImageRWops;
@@ -11,7 +12,7 @@ use sdl2::surface::Surface;
 use crate::errors::SdlError;
 use anyhow::{bail, Result};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct SpriteDescription {
     pub number_of_frames: usize,
     pub border_left: usize,
@@ -19,9 +20,64 @@ pub struct SpriteDescription {
     pub frame_dimensions: (usize, usize),
 }
 
+/// Pixels with at least this alpha value are considered solid for collision detection
+const MASK_ALPHA_THRESHOLD: u8 = 128;
+
+/// Solid pixels of a single sprite frame, one bit per pixel
+#[derive(Debug, Clone)]
+pub struct FrameMask {
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+    bits: Vec<u64>,
+}
+
+impl FrameMask {
+    pub(crate) fn from_alpha<F>(width: usize, height: usize, alpha: F) -> Self
+    where
+        F: Fn(usize, usize) -> u8,
+    {
+        let words_per_row = width.div_ceil(64);
+        let mut bits = vec![0u64; words_per_row * height];
+
+        for y in 0..height {
+            for x in 0..width {
+                if alpha(x, y) >= MASK_ALPHA_THRESHOLD {
+                    bits[y * words_per_row + x / 64] |= 1 << (x % 64);
+                }
+            }
+        }
+
+        Self {
+            width,
+            height,
+            words_per_row,
+            bits,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Whether the pixel at (x, y) of the frame is solid, `false` outside of the frame
+    pub fn is_opaque(&self, x: usize, y: usize) -> bool {
+        if x >= self.width || y >= self.height {
+            return false;
+        }
+
+        self.bits[y * self.words_per_row + x / 64] & (1 << (x % 64)) != 0
+    }
+}
+
 pub struct Sprite<'t> {
-    texture: Texture<'t>,
+    texture: Option<Texture<'t>>,
     description: SpriteDescription,
+    masks: Arc<Vec<FrameMask>>,
 }
 
 impl<'t> Sprite<'t> {
@@ -49,13 +105,24 @@ impl<'t> Sprite<'t> {
             bail!("Height according to sprite description exceeds texture height [sprite description: {:#?}, texture properties: {:#?}", description, texture_query);
         }
 
+        let masks = Self::create_masks(&description, &alpha_map);
+
         Ok(Self {
-            texture,
+            texture: Some(texture),
             description,
+            masks: Arc::new(masks),
         })
     }
 
-    #[allow(dead_code)]
+    /// Sprite without any texture data, used when simulating the game without a renderer
+    pub fn without_texture(description: SpriteDescription) -> Sprite<'t> {
+        Self {
+            texture: None,
+            description,
+            masks: Arc::new(Vec::new()),
+        }
+    }
+
     pub fn create_placeholder_circle<T>(
         radius: u32,
         color: Color,
@@ -80,18 +147,115 @@ impl<'t> Sprite<'t> {
         let texture = surface.as_texture(texture_creator)?;
 
         Ok(Self {
-            texture,
+            texture: Some(texture),
             description: SpriteDescription {
                 number_of_frames: 1,
                 border_left: 0,
                 border_up: 0,
                 frame_dimensions: (diam as usize, diam as usize),
             },
+            masks: Arc::new(Vec::new()),
+        })
+    }
+
+    /// Tile sheet with one solid tile per color, each with a darker edge at its top and left
+    pub fn create_placeholder_tiles<T>(
+        tile_size: u32,
+        colors: &[Color],
+        texture_creator: &'t TextureCreator<T>,
+    ) -> Result<Sprite<'t>> {
+        let mut surface = Surface::new(
+            tile_size * colors.len() as u32,
+            tile_size,
+            PixelFormatEnum::BGRA8888,
+        )
+        .map_err(|e| {
+            SdlError::PlaceHolderCreateError(format!("Could not create surface: {}", e))
+        })?;
+
+        for (i, color) in colors.iter().enumerate() {
+            let left = i as i32 * tile_size as i32;
+            let edge = Color::RGB(color.r / 2, color.g / 2, color.b / 2);
+
+            for (rect, color) in [
+                (Rect::new(left, 0, tile_size, tile_size), edge),
+                (Rect::new(left + 1, 1, tile_size - 1, tile_size - 1), *color),
+            ] {
+                surface.fill_rect(rect, color).map_err(|e| {
+                    SdlError::PlaceHolderCreateError(format!("Failed to fill tile: {}", e))
+                })?;
+            }
+        }
+
+        let texture = surface.as_texture(texture_creator)?;
+
+        Ok(Self {
+            texture: Some(texture),
+            description: SpriteDescription {
+                number_of_frames: colors.len(),
+                border_left: 0,
+                border_up: 0,
+                frame_dimensions: (tile_size as usize, tile_size as usize),
+            },
+            masks: Arc::new(Vec::new()),
+        })
+    }
+
+    /// White glyphs on a transparent background, one frame per glyph
+    ///
+    /// Every glyph is given as rows of `#` (set) and `.` (transparent) pixels.
+    pub fn create_placeholder_glyphs<T>(
+        width: u32,
+        height: u32,
+        glyphs: &[&[&str]],
+        texture_creator: &'t TextureCreator<T>,
+    ) -> Result<Sprite<'t>> {
+        let mut surface = Surface::new(
+            width * glyphs.len() as u32,
+            height,
+            PixelFormatEnum::BGRA8888,
+        )
+        .map_err(|e| {
+            SdlError::PlaceHolderCreateError(format!("Could not create surface: {}", e))
+        })?;
+
+        for (i, glyph) in glyphs.iter().enumerate() {
+            for (y, row) in glyph.iter().enumerate() {
+                for (x, _) in row.chars().enumerate().filter(|(_, pixel)| *pixel == '#') {
+                    let rect = Rect::new(i as i32 * width as i32 + x as i32, y as i32, 1, 1);
+                    surface.fill_rect(rect, Color::WHITE).map_err(|e| {
+                        SdlError::PlaceHolderCreateError(format!("Failed to draw glyph: {}", e))
+                    })?;
+                }
+            }
+        }
+
+        let mut texture = surface.as_texture(texture_creator)?;
+        texture.set_blend_mode(BlendMode::Blend);
+
+        Ok(Self {
+            texture: Some(texture),
+            description: SpriteDescription {
+                number_of_frames: glyphs.len(),
+                border_left: 0,
+                border_up: 0,
+                frame_dimensions: (width as usize, height as usize),
+            },
+            masks: Arc::new(Vec::new()),
         })
     }
 
     pub fn texture(&self) -> &Texture<'t> {
-        &self.texture
+        self.texture
+            .as_ref()
+            .expect("sprite should have been loaded with a texture")
+    }
+
+    /// Texture for changing its color or alpha modulation
+    pub fn texture_mut(&mut self) -> &mut Texture<'t> {
+        self.texture
+            .as_mut()
+            .expect("sprite should have been loaded with a texture")
     }
 
     pub fn frame_width(&self) -> usize {
@@ -103,19 +267,51 @@ impl<'t> Sprite<'t> {
     }
 
     pub fn get_rect_of_frame(&self, frame: usize) -> Option<Rect> {
-        if frame < self.description.number_of_frames {
+        Self::rect_of_frame(&self.description, frame)
+    }
+
+    /// Collision mask of the given frame, `None` if the sprite was loaded without alpha map
+    pub fn mask_of_frame(&self, frame: usize) -> Option<&FrameMask> {
+        self.masks.get(frame)
+    }
+
+    fn rect_of_frame(description: &SpriteDescription, frame: usize) -> Option<Rect> {
+        if frame < description.number_of_frames {
             Some(Rect::new(
-                (frame * self.description.frame_dimensions.0
-                    + (frame + 1) * self.description.border_left) as i32,
-                self.description.border_up as i32,
-                self.description.frame_dimensions.0 as u32,
-                self.description.frame_dimensions.1 as u32,
+                (frame * description.frame_dimensions.0 + (frame + 1) * description.border_left)
+                    as i32,
+                description.border_up as i32,
+                description.frame_dimensions.0 as u32,
+                description.frame_dimensions.1 as u32,
             ))
         } else {
             None
         }
     }
 
+    fn create_masks(description: &SpriteDescription, alpha_map: &Surface) -> Vec<FrameMask> {
+        let pitch = alpha_map.pitch() as usize;
+        let pixels_alpha = alpha_map
+            .without_lock()
+            .expect("surface doesn't require locking");
+
+        (0..description.number_of_frames)
+            .map(|frame| {
+                let rect = Self::rect_of_frame(description, frame).expect("frame should exist");
+                FrameMask::from_alpha(rect.width() as usize, rect.height() as usize, |x, y| {
+                    let offset = (rect.y() as usize + y) * pitch + (rect.x() as usize + x) * 4;
+                    Self::alpha_of_pixel(&pixels_alpha[offset..offset + 4])
+                })
+            })
+            .collect()
+    }
+
+    /// Alpha value of a pixel of the (grayscale) alpha map, white means fully transparent
+    fn alpha_of_pixel(pixel: &[u8]) -> u8 {
+        let grayscale = ((pixel[0] as u32 + pixel[1] as u32 + pixel[2] as u32) / 3) as u8;
+        255 - grayscale
+    }
+
     fn load_sprite_from_gif<P>(path: P) -> Result<Surface<'static>, SdlError>
     where
         P: AsRef<Path>,
@@ -147,8 +343,7 @@ impl<'t> Sprite<'t> {
 
         // FIXME: the following has endianness problems, should query byte layout and change accordingly
         for (i, pixel) in pixels_alpha.chunks(4).enumerate() {
-            let grayscale = ((pixel[0] as u32 + pixel[1] as u32 + pixel[2] as u32) / 3) as u8;
-            pixels_target[i * 4] = 255 - grayscale;
+            pixels_target[i * 4] = Self::alpha_of_pixel(pixel);
         }
 
         let mut texture = target_surface.as_texture(texture_creator)?;
@@ -161,6 +356,7 @@ impl<'t> Sprite<'t> {
 #[derive(Debug, Copy, Clone)]
 pub struct SpriteId(usize);
 
+#[derive(Default)]
 pub struct SpriteManager<'t> {
     sprites: Vec<Sprite<'t>>,
 }
@@ -181,7 +377,34 @@ impl<'t> SpriteManager<'t> {
         &self.sprites[id.0]
     }
 
+    pub fn get_mut(&mut self, id: SpriteId) -> &mut Sprite<'t> {
+        &mut self.sprites[id.0]
+    }
+
     pub fn get_description(&self, id: SpriteId) -> &SpriteDescription {
         &self.sprites[id.0].description
     }
+
+    /// Collision masks of all sprites loaded so far
+    pub fn masks(&self) -> SpriteMasks {
+        SpriteMasks {
+            masks: self
+                .sprites
+                .iter()
+                .map(|sprite| Arc::clone(&sprite.masks))
+                .collect(),
+        }
+    }
+}
+
+/// Collision masks of all sprites, available to the game systems without access to textures
+#[derive(Default, Clone)]
+pub struct SpriteMasks {
+    masks: Vec<Arc<Vec<FrameMask>>>,
+}
+
+impl SpriteMasks {
+    pub fn get(&self, id: SpriteId, frame: usize) -> Option<&FrameMask> {
+        self.masks.get(id.0).and_then(|masks| masks.get(frame))
+    }
 }
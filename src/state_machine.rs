@@ -0,0 +1,238 @@
+use std::{collections::HashMap, fs, path::Path, sync::Arc};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+/// Value of the animated entity a transition can depend on
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+pub enum Parameter {
+    /// Acceleration in pixels / s^2
+    Ax,
+    Ay,
+    /// Velocity in pixels / s
+    Vx,
+    Vy,
+}
+
+/// Condition of a transition as written in the animation file
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
+pub enum Condition {
+    Less(Parameter, f32),
+    Greater(Parameter, f32),
+    Equal(Parameter, f32),
+    AtMost(Parameter, f32),
+    AtLeast(Parameter, f32),
+    /// The weapon of the entity fired this tick
+    Fired,
+}
+
+/// Parameter values of an entity for a single tick
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Parameters {
+    pub ax: f32,
+    pub ay: f32,
+    pub vx: f32,
+    pub vy: f32,
+    pub fired: bool,
+}
+
+impl Parameters {
+    fn get(&self, parameter: Parameter) -> f32 {
+        match parameter {
+            Parameter::Ax => self.ax,
+            Parameter::Ay => self.ay,
+            Parameter::Vx => self.vx,
+            Parameter::Vy => self.vy,
+        }
+    }
+}
+
+impl Condition {
+    pub fn holds(&self, parameters: &Parameters) -> bool {
+        match *self {
+            Condition::Less(parameter, value) => parameters.get(parameter) < value,
+            Condition::Greater(parameter, value) => parameters.get(parameter) > value,
+            Condition::Equal(parameter, value) => parameters.get(parameter) == value,
+            Condition::AtMost(parameter, value) => parameters.get(parameter) <= value,
+            Condition::AtLeast(parameter, value) => parameters.get(parameter) >= value,
+            Condition::Fired => parameters.fired,
+        }
+    }
+}
+
+/// Transition as written in the animation file
+#[derive(Debug, Clone, Deserialize)]
+pub struct TransitionDefinition {
+    /// Name of the next state
+    pub to: String,
+    /// All conditions must hold, always taken without any
+    #[serde(default)]
+    pub when: Vec<Condition>,
+}
+
+/// State as written in the animation file
+#[derive(Debug, Clone, Deserialize)]
+pub struct StateDefinition {
+    pub name: String,
+    /// Sprite frame shown in this state, the frame is left as is without one
+    #[serde(default)]
+    pub frame: Option<usize>,
+    /// Sprite scale factor in this state, the scale is left as is without one
+    #[serde(default)]
+    pub scale: Option<f32>,
+    /// Minimum time in the state before its transitions are checked, in s
+    #[serde(default)]
+    pub duration: f32,
+    /// Checked in order, the first one whose conditions hold is taken
+    #[serde(default)]
+    pub transitions: Vec<TransitionDefinition>,
+}
+
+/// State machine as written in the animation file
+#[derive(Debug, Clone, Deserialize)]
+pub struct StateMachineDefinition {
+    pub initial: String,
+    pub states: Vec<StateDefinition>,
+    /// Checked every tick before the transitions of the current state, even before its duration
+    /// has passed
+    #[serde(default)]
+    pub any_state: Vec<TransitionDefinition>,
+}
+
+/// Transition with the next state resolved to its index
+#[derive(Debug, Clone)]
+pub struct Transition {
+    pub to: usize,
+    pub when: Vec<Condition>,
+}
+
+impl Transition {
+    pub fn holds(&self, parameters: &Parameters) -> bool {
+        self.when
+            .iter()
+            .all(|condition| condition.holds(parameters))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct State {
+    pub name: String,
+    pub frame: Option<usize>,
+    pub scale: Option<f32>,
+    pub duration: f32,
+    pub transitions: Vec<Transition>,
+}
+
+/// Animation state machine with all state names resolved
+#[derive(Debug, Clone)]
+pub struct StateMachine {
+    pub initial: usize,
+    pub states: Vec<State>,
+    pub any_state: Vec<Transition>,
+}
+
+impl StateMachine {
+    pub fn from_definition(definition: StateMachineDefinition) -> Result<Self> {
+        let mut indices = HashMap::new();
+        for (idx, state) in definition.states.iter().enumerate() {
+            if indices.insert(state.name.as_str(), idx).is_some() {
+                bail!("duplicate state \"{}\"", state.name);
+            }
+        }
+
+        let resolve = |transitions: &[TransitionDefinition]| -> Result<Vec<Transition>> {
+            transitions
+                .iter()
+                .map(|transition| {
+                    Ok(Transition {
+                        to: *indices
+                            .get(transition.to.as_str())
+                            .with_context(|| format!("unknown state \"{}\"", transition.to))?,
+                        when: transition.when.clone(),
+                    })
+                })
+                .collect()
+        };
+
+        let initial = *indices
+            .get(definition.initial.as_str())
+            .with_context(|| format!("unknown initial state \"{}\"", definition.initial))?;
+        let any_state = resolve(&definition.any_state)?;
+        let states = definition
+            .states
+            .iter()
+            .map(|state| {
+                if state.duration < 0.0 {
+                    bail!("state \"{}\" has a negative duration", state.name);
+                }
+
+                Ok(State {
+                    name: state.name.clone(),
+                    frame: state.frame,
+                    scale: state.scale,
+                    duration: state.duration,
+                    transitions: resolve(&state.transitions)
+                        .with_context(|| format!("invalid state \"{}\"", state.name))?,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            initial,
+            states,
+            any_state,
+        })
+    }
+
+    /// Check the frames of all states against the number of frames of a sprite
+    pub fn validate_frames(&self, number_of_frames: usize) -> Result<()> {
+        for state in &self.states {
+            if let Some(frame) = state.frame.filter(|&frame| frame >= number_of_frames) {
+                bail!(
+                    "frame {} of state \"{}\" is out of range, the sprite has {} frames",
+                    frame,
+                    state.name,
+                    number_of_frames
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// All animation state machines, by name
+#[derive(Default)]
+pub struct StateMachineLibrary {
+    machines: HashMap<String, Arc<StateMachine>>,
+}
+
+impl StateMachineLibrary {
+    /// Load state machines from a RON file mapping names to state machines
+    pub fn load<P: AsRef<Path>>(file: P) -> Result<Self> {
+        let file = file.as_ref();
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("Failed to read animation file {}", file.display()))?;
+
+        Self::parse(&content).with_context(|| format!("Invalid animation file {}", file.display()))
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        let definitions: HashMap<String, StateMachineDefinition> = ron::from_str(content)?;
+
+        let machines = definitions
+            .into_iter()
+            .map(|(name, definition)| {
+                let machine = StateMachine::from_definition(definition)
+                    .with_context(|| format!("invalid state machine \"{}\"", name))?;
+                Ok((name, Arc::new(machine)))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { machines })
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<StateMachine>> {
+        self.machines.get(name).cloned()
+    }
+}
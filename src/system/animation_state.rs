@@ -0,0 +1,73 @@
+use specs::{Entities, Join, Read, ReadStorage, System, WriteStorage};
+
+use crate::{
+    component::{
+        animation_state::AnimationStateComponent, bullet_emitter::BulletEmitterComponent,
+        player_physics::PlayerPhysicsComponent, player_weapon::PlayerWeaponComponent,
+        position::PositionComponent, sprite::SpriteComponent,
+    },
+    resource::timing::Timing,
+    state_machine::Parameters,
+};
+
+/// Evaluates animation state machines and applies the frame and scale of their state to the
+/// sprite
+pub struct AnimationStateSystem;
+
+impl<'sys> System<'sys> for AnimationStateSystem {
+    type SystemData = (
+        Entities<'sys>,
+        Read<'sys, Timing>,
+        ReadStorage<'sys, PlayerPhysicsComponent>,
+        ReadStorage<'sys, PositionComponent>,
+        ReadStorage<'sys, PlayerWeaponComponent>,
+        ReadStorage<'sys, BulletEmitterComponent>,
+        WriteStorage<'sys, AnimationStateComponent>,
+        WriteStorage<'sys, SpriteComponent>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            timing,
+            physics,
+            position,
+            player_weapon,
+            bullet_emitter,
+            mut animation,
+            mut sprite,
+        ) = data;
+        let dt = timing.delta_time.as_secs_f32();
+
+        for (entity, animation, sprite) in (&entities, &mut animation, &mut sprite).join() {
+            let source = animation.parameter_source.unwrap_or(entity);
+
+            let mut parameters = Parameters {
+                fired: player_weapon.get(source).is_some_and(|weapon| weapon.fired)
+                    || bullet_emitter
+                        .get(source)
+                        .is_some_and(|emitter| emitter.fired),
+                ..Parameters::default()
+            };
+            if let Some(physics) = physics.get(source) {
+                parameters.ax = physics.ax;
+                parameters.ay = physics.ay;
+                parameters.vx = physics.vx;
+                parameters.vy = physics.vy;
+            } else if let Some(position) = position.get(source).filter(|_| dt > 0.0) {
+                // Entities moved by other systems only have a velocity
+                parameters.vx = (position.x() - position.previous_x()) / dt;
+                parameters.vy = (position.y() - position.previous_y()) / dt;
+            }
+
+            animation.update(dt, &parameters);
+
+            if let Some(frame) = animation.frame() {
+                sprite.current_frame_idx = frame;
+            }
+            if let Some(scale) = animation.scale() {
+                sprite.scale_factor = scale;
+            }
+        }
+    }
+}
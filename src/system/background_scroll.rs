@@ -0,0 +1,23 @@
+use specs::{Read, System, Write};
+
+use crate::{
+    level::LevelDirector,
+    resource::{background::Background, timing::Timing},
+};
+
+/// Scrolls the background planes with the level
+pub struct BackgroundScrollSystem;
+
+impl<'sys> System<'sys> for BackgroundScrollSystem {
+    type SystemData = (
+        Read<'sys, Timing>,
+        Read<'sys, LevelDirector>,
+        Write<'sys, Background>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (timing, director, mut background) = data;
+
+        background.scroll(director.scroll_speed() * timing.delta_time.as_secs_f32());
+    }
+}
@@ -0,0 +1,55 @@
+use log::{error, info};
+use specs::{Entities, Join, ReadExpect, ReadStorage, System, WriteStorage};
+
+use crate::{
+    component::{
+        bounding_box::{BoundingBoxComponent, BoundsPolicy},
+        position::PositionComponent,
+    },
+    resource::playfield::Playfield,
+};
+
+pub struct BoundsSystem;
+
+impl<'sys> System<'sys> for BoundsSystem {
+    type SystemData = (
+        Entities<'sys>,
+        ReadExpect<'sys, Playfield>,
+        ReadStorage<'sys, BoundingBoxComponent>,
+        WriteStorage<'sys, PositionComponent>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, playfield, bounding_box, mut position) = data;
+
+        for (e, bounding_box, position) in (&entities, &bounding_box, &mut position).join() {
+            let half_width = bounding_box.dimensions.0 / 2.0;
+            let half_height = bounding_box.dimensions.1 / 2.0;
+
+            match bounding_box.policy {
+                BoundsPolicy::Clamp { margin } => {
+                    position.clamp(
+                        margin + half_width,
+                        playfield.width - margin - half_width,
+                        margin + half_height,
+                        playfield.height - margin - half_height,
+                    );
+                }
+                BoundsPolicy::Despawn => {
+                    if position.x() < -half_width
+                        || position.x() > playfield.width + half_width
+                        || position.y() < -half_height
+                        || position.y() > playfield.height + half_height
+                    {
+                        let err = entities.delete(e);
+                        if let Err(e) = err {
+                            error!(target: "BoundsSystem", "{}", e);
+                        } else {
+                            info!(target: "BoundsSystem", "Entity left playfield, delete it");
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
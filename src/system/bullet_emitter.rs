@@ -0,0 +1,70 @@
+use specs::{Builder, Entities, Join, LazyUpdate, Read, ReadStorage, System, WriteStorage};
+
+use crate::{
+    component::{
+        bounding_box::{BoundingBoxComponent, BoundsPolicy},
+        bullet_emitter::BulletEmitterComponent,
+        bullet_physics::BulletPhysicsComponent,
+        damage::DamageComponent,
+        hitbox::{CollisionLayers, HitboxComponent},
+        position::PositionComponent,
+        sprite::SpriteComponent,
+    },
+    resource::{spatial_hash::SpatialHash, timing::Timing},
+    system::render::Layer,
+};
+
+/// Fires the bullet patterns of enemy weapons
+pub struct BulletEmitterSystem;
+
+impl<'sys> System<'sys> for BulletEmitterSystem {
+    type SystemData = (
+        Read<'sys, Timing>,
+        Read<'sys, SpatialHash>,
+        Entities<'sys>,
+        Read<'sys, LazyUpdate>,
+        WriteStorage<'sys, BulletEmitterComponent>,
+        ReadStorage<'sys, PositionComponent>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (timing, spatial_hash, entities, lazy_update, mut emitter, position) = data;
+        let dt = timing.delta_time.as_secs_f32();
+
+        for (emitter, emitter_position) in (&mut emitter, &position).join() {
+            let source = (
+                emitter_position.x() + emitter.offset.0,
+                emitter_position.y() + emitter.offset.1,
+            );
+
+            let target = spatial_hash
+                .nearest(source.0, source.1, |entry| {
+                    entry.layer.intersects(CollisionLayers::PLAYER)
+                })
+                .and_then(|entry| position.get(entry.entity))
+                .map(|target| (target.x(), target.y()));
+
+            let bullets = emitter.state.update(&emitter.pattern, dt, source, target);
+            emitter.fired = !bullets.is_empty();
+
+            for (vx, vy) in bullets {
+                lazy_update
+                    .create_entity(&entities)
+                    .with(SpriteComponent::new(emitter.bullet_sprite, Layer::Bullets))
+                    .with(PositionComponent::new(source.0, source.1))
+                    .with(BulletPhysicsComponent { vx, vy })
+                    .with(BoundingBoxComponent::new(
+                        emitter.bullet_hitbox.dimensions(),
+                        BoundsPolicy::Despawn,
+                    ))
+                    .with(HitboxComponent::new(
+                        emitter.bullet_hitbox,
+                        CollisionLayers::ENEMY_BULLETS,
+                        CollisionLayers::PLAYER,
+                    ))
+                    .with(DamageComponent::new(emitter.bullet_damage))
+                    .build();
+            }
+        }
+    }
+}
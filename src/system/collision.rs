@@ -0,0 +1,252 @@
+use log::debug;
+use specs::{Entity, Read, ReadExpect, ReadStorage, System, Write};
+
+use crate::{
+    component::{hitbox::HitboxComponent, position::PositionComponent, sprite::SpriteComponent},
+    resource::{
+        collision::{CollisionEvent, Collisions},
+        spatial_hash::SpatialHash,
+    },
+    sprite::{FrameMask, SpriteMasks},
+};
+
+/// Frame mask of a sprite as rendered at its current position, scaled, flipped and rotated
+/// around its pivot
+struct PlacedMask<'a> {
+    mask: &'a FrameMask,
+    left: f32,
+    top: f32,
+    scale: f32,
+    /// Point the frame is rotated around, in screen coordinates
+    pivot: (f32, f32),
+    /// Sine and cosine of the rotation
+    rotation: (f32, f32),
+    flip_horizontal: bool,
+    flip_vertical: bool,
+}
+
+impl<'a> PlacedMask<'a> {
+    fn new(mask: &'a FrameMask, sprite: &SpriteComponent, (x, y): (f32, f32)) -> Self {
+        let scale = sprite.scale_factor;
+        let width = mask.width() as f32 * scale;
+        let height = mask.height() as f32 * scale;
+        let left = x - width / 2.0;
+        let top = y - height / 2.0;
+        let pivot = match sprite.pivot {
+            Some((pivot_x, pivot_y)) => (left + pivot_x * scale, top + pivot_y * scale),
+            None => (x, y),
+        };
+
+        Self {
+            mask,
+            left,
+            top,
+            scale,
+            pivot,
+            rotation: sprite.rotation.to_radians().sin_cos(),
+            flip_horizontal: sprite.flip_horizontal,
+            flip_vertical: sprite.flip_vertical,
+        }
+    }
+
+    fn contains(&self, point: (f32, f32)) -> bool {
+        // Undo the clockwise rotation around the pivot
+        let (sin, cos) = self.rotation;
+        let dx = point.0 - self.pivot.0;
+        let dy = point.1 - self.pivot.1;
+        let x = self.pivot.0 + dx * cos + dy * sin;
+        let y = self.pivot.1 - dx * sin + dy * cos;
+
+        let mut x = (x - self.left) / self.scale;
+        let mut y = (y - self.top) / self.scale;
+        if self.flip_horizontal {
+            x = self.mask.width() as f32 - x;
+        }
+        if self.flip_vertical {
+            y = self.mask.height() as f32 - y;
+        }
+
+        x >= 0.0 && y >= 0.0 && self.mask.is_opaque(x as usize, y as usize)
+    }
+}
+
+struct Candidate<'a> {
+    entity: Entity,
+    hitbox: &'a HitboxComponent,
+    position: (f32, f32),
+    mask: Option<PlacedMask<'a>>,
+}
+
+impl<'a> Candidate<'a> {
+    fn contains(&self, point: (f32, f32)) -> bool {
+        match &self.mask {
+            Some(mask) => mask.contains(point),
+            None => self.hitbox.contains(self.position, point),
+        }
+    }
+}
+
+pub struct CollisionSystem;
+
+impl CollisionSystem {
+    /// Exact test for two candidates whose hitbox shapes are known to overlap
+    fn narrow_phase(a: &Candidate, b: &Candidate) -> bool {
+        if a.mask.is_none() && b.mask.is_none() {
+            return true;
+        }
+
+        // Sample at pixel centers within the overlap of both hitboxes
+        let (a_x_min, a_y_min, a_x_max, a_y_max) = a.hitbox.bounds(a.position.0, a.position.1);
+        let (b_x_min, b_y_min, b_x_max, b_y_max) = b.hitbox.bounds(b.position.0, b.position.1);
+
+        let x_min = f32::max(a_x_min, b_x_min).floor() as i32;
+        let x_max = f32::min(a_x_max, b_x_max).ceil() as i32;
+        let y_min = f32::max(a_y_min, b_y_min).floor() as i32;
+        let y_max = f32::min(a_y_max, b_y_max).ceil() as i32;
+
+        (y_min..y_max).any(|y| {
+            (x_min..x_max).any(|x| {
+                let point = (x as f32 + 0.5, y as f32 + 0.5);
+                a.contains(point) && b.contains(point)
+            })
+        })
+    }
+}
+
+impl<'sys> System<'sys> for CollisionSystem {
+    type SystemData = (
+        Write<'sys, Collisions>,
+        ReadExpect<'sys, SpriteMasks>,
+        Read<'sys, SpatialHash>,
+        ReadStorage<'sys, HitboxComponent>,
+        ReadStorage<'sys, PositionComponent>,
+        ReadStorage<'sys, SpriteComponent>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (mut collisions, sprite_masks, spatial_hash, hitbox, position, sprite) = data;
+
+        collisions.clear();
+
+        // Entities are indexed like the entries of the spatial hash
+        let candidates: Vec<_> = spatial_hash
+            .entries()
+            .iter()
+            .map(|entry| {
+                let entity = entry.entity;
+                let (Some(hitbox), Some(position)) = (hitbox.get(entity), position.get(entity))
+                else {
+                    return None;
+                };
+
+                // Without mask (e.g. when running headless) the shape is used as is
+                let mask = sprite
+                    .get(entity)
+                    .filter(|_| hitbox.pixel_perfect)
+                    .and_then(|sprite| {
+                        sprite_masks
+                            .get(sprite.sprite, sprite.current_frame_idx)
+                            .map(|mask| (sprite, mask))
+                    })
+                    .map(|(sprite, mask)| {
+                        PlacedMask::new(mask, sprite, (position.x(), position.y()))
+                    });
+
+                Some(Candidate {
+                    entity,
+                    hitbox,
+                    position: (position.x(), position.y()),
+                    mask,
+                })
+            })
+            .collect();
+
+        for (i, j) in spatial_hash.overlapping_pairs() {
+            let (Some(a), Some(b)) = (&candidates[i], &candidates[j]) else {
+                continue;
+            };
+
+            if !a.hitbox.interacts_with(b.hitbox)
+                || !a.hitbox.overlaps(a.position, b.hitbox, b.position)
+                || !Self::narrow_phase(a, b)
+            {
+                continue;
+            }
+
+            if a.hitbox.mask.intersects(b.hitbox.layer) {
+                collisions.push(CollisionEvent {
+                    entity: a.entity,
+                    other: b.entity,
+                });
+            }
+            if b.hitbox.mask.intersects(a.hitbox.layer) {
+                collisions.push(CollisionEvent {
+                    entity: b.entity,
+                    other: a.entity,
+                });
+            }
+
+            debug!(target: "CollisionSystem", "Collision between {:?} and {:?}", a.entity, b.entity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sdl2::pixels::Color;
+
+    use super::*;
+    use crate::{
+        assets::{AssetLoader, HeadlessAssetLoader},
+        system::render::Layer,
+    };
+
+    /// 4x2 frame with only its left column solid, centered on (10, 10)
+    fn contains(configure: impl Fn(SpriteComponent) -> SpriteComponent, point: (f32, f32)) -> bool {
+        let mask = FrameMask::from_alpha(4, 2, |x, _| if x == 0 { 255 } else { 0 });
+        let mut loader = HeadlessAssetLoader::new();
+        let sprite_id = loader.create_placeholder_circle(2, Color::WHITE).unwrap();
+        let sprite = configure(SpriteComponent::new(sprite_id, Layer::AirUnits));
+
+        PlacedMask::new(&mask, &sprite, (10.0, 10.0)).contains(point)
+    }
+
+    #[test]
+    fn masks_are_placed_like_the_rendered_sprite() {
+        let unchanged = |sprite| sprite;
+        assert!(contains(unchanged, (8.5, 9.5)));
+        assert!(!contains(unchanged, (11.5, 9.5)));
+
+        let scaled = |sprite: SpriteComponent| sprite.with_scale_factor(2.0);
+        assert!(contains(scaled, (6.5, 8.5)));
+        assert!(contains(scaled, (7.5, 11.5)));
+        assert!(!contains(scaled, (8.5, 9.5)));
+    }
+
+    #[test]
+    fn masks_are_flipped_like_the_rendered_sprite() {
+        let flipped = |sprite: SpriteComponent| sprite.with_flip(true, false);
+        assert!(contains(flipped, (11.5, 9.5)));
+        assert!(!contains(flipped, (8.5, 9.5)));
+    }
+
+    #[test]
+    fn masks_are_rotated_like_the_rendered_sprite() {
+        // Clockwise around the center, the left column ends up on top
+        let rotated = |sprite: SpriteComponent| sprite.with_rotation(90.0);
+        assert!(contains(rotated, (10.0, 8.5)));
+        assert!(!contains(rotated, (8.5, 10.0)));
+        assert!(!contains(rotated, (10.0, 11.5)));
+
+        // Around the top left corner, the left column ends up left of it
+        let pivoted = |sprite: SpriteComponent| sprite.with_rotation(90.0).with_pivot(0.0, 0.0);
+        assert!(contains(pivoted, (7.5, 9.5)));
+        assert!(contains(pivoted, (6.5, 9.5)));
+        assert!(!contains(pivoted, (8.5, 9.5)));
+
+        // Flipped before being rotated
+        let both = |sprite: SpriteComponent| sprite.with_flip(true, false).with_rotation(90.0);
+        assert!(contains(both, (10.0, 11.5)));
+        assert!(!contains(both, (10.0, 8.5)));
+    }
+}
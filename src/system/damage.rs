@@ -0,0 +1,74 @@
+use log::info;
+use specs::{Builder, Entities, LazyUpdate, Read, ReadStorage, System, WriteStorage};
+
+use crate::{
+    component::{
+        damage::DamageComponent, health::HealthComponent,
+        particle_emitter::ParticleEmitterComponent, position::PositionComponent,
+    },
+    particle::EmitterLibrary,
+    resource::collision::Collisions,
+};
+
+/// Particles emitted where a bullet hits
+const HIT_SPARKS_EMITTER: &str = "hit_sparks";
+
+/// Subtracts the damage of colliding entities from the health of the entities they hit
+pub struct DamageSystem;
+
+impl<'sys> System<'sys> for DamageSystem {
+    type SystemData = (
+        Entities<'sys>,
+        Read<'sys, Collisions>,
+        Read<'sys, LazyUpdate>,
+        Read<'sys, EmitterLibrary>,
+        ReadStorage<'sys, DamageComponent>,
+        ReadStorage<'sys, PositionComponent>,
+        WriteStorage<'sys, HealthComponent>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, collisions, lazy_update, particles, damage, position, mut health) = data;
+        let sparks = particles.get(HIT_SPARKS_EMITTER);
+
+        // A bullet is used up by its first hit, even if it overlaps several targets
+        let mut used_up = Vec::new();
+
+        for event in collisions.events() {
+            let Some(damage) = damage.get(event.other) else {
+                continue;
+            };
+            if used_up.contains(&event.other) {
+                continue;
+            }
+            let Some(health) = health.get_mut(event.entity) else {
+                continue;
+            };
+            if health.is_dead() {
+                continue;
+            }
+
+            health.health = health.health.saturating_sub(damage.damage);
+            used_up.push(event.other);
+
+            if let (Some(sparks), Some(position)) = (&sparks, position.get(event.other)) {
+                lazy_update
+                    .create_entity(&entities)
+                    .with(PositionComponent::new(position.x(), position.y()))
+                    .with(
+                        ParticleEmitterComponent::new(sparks.clone(), event.other.id() as u64)
+                            .with_despawn_when_finished(),
+                    )
+                    .build();
+            }
+
+            info!(target: "DamageSystem", "{:?} hit by {:?}, {} health left", event.entity, event.other, health.health);
+        }
+
+        for entity in used_up {
+            entities
+                .delete(entity)
+                .expect("colliding entity should be alive");
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use log::info;
+use specs::{Builder, Entities, Join, LazyUpdate, Read, ReadExpect, ReadStorage, System, Write};
+
+use crate::{
+    component::{
+        bullet_physics::BulletPhysicsComponent, explosion::ExplosionComponent,
+        health::HealthComponent, lifetime::LifetimeComponent,
+        particle_emitter::ParticleEmitterComponent, position::PositionComponent,
+        score::ScoreComponent, sprite::SpriteComponent, text::TextComponent,
+    },
+    font::FontLibrary,
+    resource::{game_state::GameState, sound::AudioInterface},
+    system::render::Layer,
+};
+
+/// Font of the points floating up from destroyed entities
+const SCORE_POPUP_FONT: &str = "small";
+
+/// Time the points stay visible in s
+const SCORE_POPUP_DURATION: f32 = 0.8;
+
+/// Velocity of the points in pixels / s
+const SCORE_POPUP_VELOCITY: f32 = -40.0;
+
+/// Destroys entities without health left, replacing them by their explosion and awarding their
+/// points
+pub struct DeathSystem;
+
+impl<'sys> System<'sys> for DeathSystem {
+    type SystemData = (
+        Entities<'sys>,
+        Read<'sys, LazyUpdate>,
+        ReadExpect<'sys, AudioInterface>,
+        Write<'sys, GameState>,
+        Read<'sys, FontLibrary>,
+        ReadStorage<'sys, HealthComponent>,
+        ReadStorage<'sys, ExplosionComponent>,
+        ReadStorage<'sys, ScoreComponent>,
+        ReadStorage<'sys, PositionComponent>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (
+            entities,
+            lazy_update,
+            audio,
+            mut game_state,
+            fonts,
+            health,
+            explosion,
+            score,
+            position,
+        ) = data;
+        let popup_font = fonts.get(SCORE_POPUP_FONT);
+
+        for (entity, health, explosion, score, position) in (
+            &entities,
+            &health,
+            explosion.maybe(),
+            score.maybe(),
+            &position,
+        )
+            .join()
+        {
+            if !health.is_dead() {
+                continue;
+            }
+
+            if let Some(explosion) = explosion {
+                lazy_update
+                    .create_entity(&entities)
+                    // Below the explosion particles
+                    .with(SpriteComponent::new(explosion.sprite, Layer::AirEffects).with_z_order(1))
+                    .with(PositionComponent::new(position.x(), position.y()))
+                    .with(LifetimeComponent {
+                        remaining: explosion.duration,
+                    })
+                    .build();
+
+                audio.play_sound(explosion.sound);
+
+                if let Some(particles) = &explosion.particles {
+                    lazy_update
+                        .create_entity(&entities)
+                        .with(PositionComponent::new(position.x(), position.y()))
+                        .with(
+                            ParticleEmitterComponent::new(particles.clone(), entity.id() as u64)
+                                .with_despawn_when_finished(),
+                        )
+                        .build();
+                }
+            }
+
+            if let Some(score) = score {
+                game_state.score += score.points as u64;
+
+                if let Some(font) = &popup_font {
+                    lazy_update
+                        .create_entity(&entities)
+                        .with(
+                            TextComponent::new(score.points.to_string(), font.clone(), Layer::Hud)
+                                .with_color(255, 230, 120),
+                        )
+                        .with(PositionComponent::new(position.x(), position.y()))
+                        .with(BulletPhysicsComponent {
+                            vx: 0.0,
+                            vy: SCORE_POPUP_VELOCITY,
+                        })
+                        .with(LifetimeComponent {
+                            remaining: SCORE_POPUP_DURATION,
+                        })
+                        .build();
+                }
+            }
+
+            entities
+                .delete(entity)
+                .expect("dead entity should still be alive");
+
+            info!(target: "DeathSystem", "{:?} destroyed", entity);
+        }
+    }
+}
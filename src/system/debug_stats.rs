@@ -0,0 +1,39 @@
+use specs::{Join, RunNow, World, WorldExt};
+
+use crate::{
+    component::{self, particle_emitter::ParticleEmitterComponent},
+    resource::debug::DebugOverlay,
+};
+
+/// Counts entities and components for the debug overlay while it is enabled
+///
+/// Runs on the whole world instead of declaring its storages, so every registered component is
+/// counted.
+pub struct DebugStatsSystem;
+
+impl<'a> RunNow<'a> for DebugStatsSystem {
+    fn run_now(&mut self, world: &'a World) {
+        if !world.read_resource::<DebugOverlay>().enabled {
+            return;
+        }
+
+        let mut counts = vec![("ENTITIES", world.entities().join().count())];
+        counts.extend(component::count_all(world));
+        counts.push((
+            "PARTICLES",
+            world
+                .read_storage::<ParticleEmitterComponent>()
+                .join()
+                .map(|emitter| emitter.particles.len())
+                .sum(),
+        ));
+
+        world.write_resource::<DebugOverlay>().entity_counts = counts;
+    }
+
+    fn setup(&mut self, world: &mut World) {
+        world
+            .entry::<DebugOverlay>()
+            .or_insert_with(DebugOverlay::default);
+    }
+}
@@ -0,0 +1,51 @@
+use log::info;
+use specs::{Entities, LazyUpdate, Read, ReadExpect, System, Write};
+
+use crate::{
+    entity::enemy::Enemy,
+    level::{DirectorEvent, LevelDirector},
+    resource::{sound::AudioInterface, timing::Timing},
+};
+
+/// Triggers the events of the level timeline
+pub struct LevelDirectorSystem;
+
+impl<'sys> System<'sys> for LevelDirectorSystem {
+    type SystemData = (
+        Read<'sys, Timing>,
+        Write<'sys, LevelDirector>,
+        ReadExpect<'sys, AudioInterface>,
+        Entities<'sys>,
+        Read<'sys, LazyUpdate>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (timing, mut director, audio, entities, lazy_update) = data;
+        let dt = timing.delta_time.as_secs_f32();
+
+        for event in director.advance(dt) {
+            match event {
+                DirectorEvent::Spawn {
+                    enemy,
+                    position,
+                    path,
+                } => {
+                    Enemy::create_enemy(
+                        lazy_update.create_entity(&entities),
+                        &enemy,
+                        position.0,
+                        position.1,
+                        path,
+                    );
+                }
+                DirectorEvent::Music(file) => audio.play_music(&file),
+                DirectorEvent::BossWarning(_) => {
+                    info!(target: "LevelDirectorSystem", "Boss approaching");
+                }
+                DirectorEvent::EndStage => {
+                    info!(target: "LevelDirectorSystem", "Stage \"{}\" complete", director.name());
+                }
+            }
+        }
+    }
+}
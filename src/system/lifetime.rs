@@ -0,0 +1,27 @@
+use specs::{Entities, Join, Read, System, WriteStorage};
+
+use crate::{component::lifetime::LifetimeComponent, resource::timing::Timing};
+
+pub struct LifetimeSystem;
+
+impl<'sys> System<'sys> for LifetimeSystem {
+    type SystemData = (
+        Entities<'sys>,
+        Read<'sys, Timing>,
+        WriteStorage<'sys, LifetimeComponent>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, timing, mut lifetime) = data;
+        let dt = timing.delta_time.as_secs_f32();
+
+        for (entity, lifetime) in (&entities, &mut lifetime).join() {
+            lifetime.remaining -= dt;
+            if lifetime.remaining <= 0.0 {
+                entities
+                    .delete(entity)
+                    .expect("expired entity should be alive");
+            }
+        }
+    }
+}
@@ -1,6 +1,19 @@
+pub mod animation_state;
+pub mod background_scroll;
+pub mod bounds;
+pub mod bullet_emitter;
 pub mod bullet_physics;
-pub mod player_animation;
+pub mod collision;
+pub mod damage;
+pub mod death;
+pub mod debug_stats;
+pub mod level_director;
+pub mod lifetime;
+pub mod particle;
+pub mod path_follow;
 pub mod player_movement;
 pub mod player_weapon;
 pub mod render;
+pub mod spatial_hash;
+pub mod sprite_animation;
 pub mod track_position;
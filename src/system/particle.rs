@@ -0,0 +1,33 @@
+use specs::{Entities, Join, Read, ReadStorage, System, WriteStorage};
+
+use crate::{
+    component::{particle_emitter::ParticleEmitterComponent, position::PositionComponent},
+    resource::timing::Timing,
+};
+
+/// Simulates the particles of all emitters and removes finished one-shot emitters
+pub struct ParticleSystem;
+
+impl<'sys> System<'sys> for ParticleSystem {
+    type SystemData = (
+        Entities<'sys>,
+        Read<'sys, Timing>,
+        ReadStorage<'sys, PositionComponent>,
+        WriteStorage<'sys, ParticleEmitterComponent>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, timing, position, mut emitter) = data;
+        let dt = timing.delta_time.as_secs_f32();
+
+        for (entity, position, emitter) in (&entities, &position, &mut emitter).join() {
+            emitter.update(dt, (position.x(), position.y()));
+
+            if emitter.despawn_when_finished && emitter.is_finished() {
+                entities
+                    .delete(entity)
+                    .expect("finished emitter should be alive");
+            }
+        }
+    }
+}
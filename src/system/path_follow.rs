@@ -0,0 +1,42 @@
+use specs::{Entities, Join, Read, System, WriteStorage};
+
+use crate::{
+    component::{path_follow::PathFollowComponent, position::PositionComponent},
+    path::PathEnd,
+    resource::timing::Timing,
+};
+
+pub struct PathFollowSystem;
+
+impl<'sys> System<'sys> for PathFollowSystem {
+    type SystemData = (
+        Entities<'sys>,
+        Read<'sys, Timing>,
+        WriteStorage<'sys, PathFollowComponent>,
+        WriteStorage<'sys, PositionComponent>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, timing, mut path_follow, mut position) = data;
+        let dt = timing.delta_time.as_secs_f32();
+
+        for (entity, path_follow, position) in (&entities, &mut path_follow, &mut position).join() {
+            path_follow.elapsed += dt;
+
+            // Positions are evaluated from the elapsed time, so no error accumulates
+            let offset = match path_follow.path.offset_at(path_follow.elapsed) {
+                Some(offset) => offset,
+                None if path_follow.path.end() == PathEnd::Despawn => {
+                    entities
+                        .delete(entity)
+                        .expect("entity at the end of its path should be alive");
+                    continue;
+                }
+                None => path_follow.path.last_offset(),
+            };
+
+            position.update_x(path_follow.origin.0 + offset.0);
+            position.update_y(path_follow.origin.1 + offset.1);
+        }
+    }
+}
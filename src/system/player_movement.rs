@@ -2,78 +2,70 @@ use specs::{Join, Read, System, WriteStorage};
 
 use crate::{
     component::{player_physics::PlayerPhysicsComponent, position::PositionComponent},
-    resource::player_input::PlayerInput,
+    resource::{player_input::PlayerInput, timing::Timing},
 };
 
 pub struct PlayerMovementSystem;
 
+impl PlayerMovementSystem {
+    /// Acceleration and velocity along one axis after a time step of `dt`
+    ///
+    /// Accelerates in `direction` (-1.0, 0.0 or 1.0), without input the velocity is
+    /// reduced to zero as fast as possible without overshooting.
+    fn update_axis(direction: f32, v: f32, a_max: f32, v_max: f32, dt: f32) -> (f32, f32) {
+        if direction != 0.0 {
+            let a = direction * a_max;
+            (a, f32::clamp(v + a * dt, -v_max, v_max))
+        } else if v == 0.0 {
+            (0.0, 0.0)
+        } else if v.abs() <= a_max * dt {
+            // Come to a full stop within this time step
+            (-v / dt, 0.0)
+        } else {
+            let a = -v.signum() * a_max;
+            (a, v + a * dt)
+        }
+    }
+}
+
 impl<'sys> System<'sys> for PlayerMovementSystem {
     type SystemData = (
         Read<'sys, PlayerInput>,
+        Read<'sys, Timing>,
         WriteStorage<'sys, PlayerPhysicsComponent>,
         WriteStorage<'sys, PositionComponent>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (player_input, mut physics, mut position) = data;
+        let (player_input, timing, mut physics, mut position) = data;
+        let dt = timing.delta_time.as_secs_f32();
 
-        for (physics, position) in (&mut physics, &mut position).join() {
-            // Acceleration:
-            if player_input.left {
-                physics.ax = -physics.ax_max;
-            } else if player_input.right {
-                physics.ax = physics.ax_max;
-            } else if physics.vx < 0.0 {
-                physics.ax = f32::min(physics.ax_max, -physics.vx);
-            } else if physics.vx > 0.0 {
-                physics.ax = f32::max(-physics.ax_max, -physics.vx);
-            } else {
-                physics.ax = 0.0;
-            }
-
-            if player_input.up {
-                physics.ay = -physics.ay_max;
-            } else if player_input.down {
-                physics.ay = physics.ay_max;
-            } else if physics.vy < 0.0 {
-                physics.ay = f32::min(physics.ay_max, -physics.vy);
-            } else if physics.vy > 0.0 {
-                physics.ay = f32::max(-physics.ay_max, -physics.vy);
-            } else {
-                physics.ay = 0.0;
-            }
+        let direction_x = if player_input.left {
+            -1.0
+        } else if player_input.right {
+            1.0
+        } else {
+            0.0
+        };
 
-            // Velocity:
-            physics.vx += physics.ax;
-            if physics.vx > physics.vx_max {
-                physics.vx = physics.vx_max;
-            } else if physics.vx < -physics.vx_max {
-                physics.vx = -physics.vx_max;
-            }
+        let direction_y = if player_input.up {
+            -1.0
+        } else if player_input.down {
+            1.0
+        } else {
+            0.0
+        };
 
-            physics.vy += physics.ay;
-            if physics.vy > physics.vy_max {
-                physics.vy = physics.vy_max;
-            } else if physics.vy < -physics.vy_max {
-                physics.vy = -physics.vy_max;
-            }
-
-            // Position:
-            let mut x = position.x() + physics.vx;
-            if x < physics.x_min {
-                x = physics.x_min;
-            } else if x > physics.x_max {
-                x = physics.x_max;
-            }
-            position.update_x(x);
+        for (physics, position) in (&mut physics, &mut position).join() {
+            // Acceleration / velocity:
+            (physics.ax, physics.vx) =
+                Self::update_axis(direction_x, physics.vx, physics.ax_max, physics.vx_max, dt);
+            (physics.ay, physics.vy) =
+                Self::update_axis(direction_y, physics.vy, physics.ay_max, physics.vy_max, dt);
 
-            let mut y = position.y() + physics.vy;
-            if y < physics.y_min {
-                y = physics.y_min;
-            } else if y > physics.y_max {
-                y = physics.y_max;
-            }
-            position.update_y(y);
+            // Position (staying within the playfield is handled by the bounds system):
+            position.update_x(position.x() + physics.vx * dt);
+            position.update_y(position.y() + physics.vy * dt);
         }
     }
 }
@@ -5,18 +5,25 @@ use specs::{
 
 use crate::{
     component::{
+        bounding_box::{BoundingBoxComponent, BoundsPolicy},
         bullet_physics::BulletPhysicsComponent,
-        player_animation::{GlowAnimationState, PlayerAnimationComponent},
+        damage::DamageComponent,
+        hitbox::{CollisionLayers, HitboxComponent},
         player_weapon::PlayerWeaponComponent,
         position::PositionComponent,
         sprite::SpriteComponent,
     },
-    resource::{player_input::PlayerInput, sound::AudioInterface},
+    resource::{
+        player_input::PlayerInput,
+        sound::AudioInterface,
+        timing::{overshoot, Timing, TIME_TOLERANCE},
+    },
     system::render::Layer,
-    FRAME_RATE_GAME, GAME_HEIGHT, GAME_WIDTH,
+    GAME_HEIGHT, GAME_WIDTH,
 };
 
-const VY: f32 = -0.000625 * GAME_HEIGHT as f32 * (1000.0 / (FRAME_RATE_GAME as f32));
+/// Bullet velocity in pixels / s
+const VY: f32 = -0.000625 * GAME_HEIGHT as f32 * 1000.0;
 const POS_OFFSET: f32 = 0.007292 * GAME_WIDTH as f32;
 
 pub struct PlayerWeaponSystem;
@@ -26,71 +33,90 @@ impl PlayerWeaponSystem {
     where
         B: Builder,
     {
-        builder
-            .with(SpriteComponent::new(weapon.bullet_sprite, Layer::Effects))
+        let mut builder = builder
+            .with(SpriteComponent::new(weapon.bullet_sprite, Layer::Bullets))
             .with(position)
-            .with(BulletPhysicsComponent {
-                vx: 0.0,
-                vy: VY,
-                x_min: -(weapon.bullet_dimensions.0 as f32 / 2.0),
-                x_max: GAME_WIDTH as f32 + weapon.bullet_dimensions.0 as f32 / 2.0,
-                y_min: -(weapon.bullet_dimensions.1 as f32 / 2.0),
-                y_max: GAME_HEIGHT as f32 + weapon.bullet_dimensions.1 as f32 / 2.0,
-            })
-            .build();
+            .with(BulletPhysicsComponent { vx: 0.0, vy: VY })
+            .with(BoundingBoxComponent::new(
+                weapon.bullet_hitbox.dimensions(),
+                BoundsPolicy::Despawn,
+            ))
+            .with(HitboxComponent::new(
+                weapon.bullet_hitbox,
+                CollisionLayers::PLAYER_BULLETS,
+                CollisionLayers::ENEMIES,
+            ))
+            .with(DamageComponent::new(weapon.bullet_damage));
+        if let Some(animation) = &weapon.bullet_animation {
+            builder = builder.with(animation.clone());
+        }
+        builder.build();
     }
 }
 
 impl<'sys> System<'sys> for PlayerWeaponSystem {
     type SystemData = (
         Read<'sys, PlayerInput>,
+        Read<'sys, Timing>,
         ReadExpect<'sys, AudioInterface>,
         Entities<'sys>,
         Read<'sys, LazyUpdate>,
         WriteStorage<'sys, PlayerWeaponComponent>,
         ReadStorage<'sys, PositionComponent>,
-        WriteStorage<'sys, PlayerAnimationComponent>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (player_input, audio, entities, lazy_update, mut weapon, position, mut animation) =
-            data;
-
-        for (weapon, position, animation) in (&mut weapon, &position, &mut animation).join() {
-            if weapon.cooldown > 0 {
-                weapon.cooldown -= 1;
-            } else if player_input.shoot_air {
-                weapon.cooldown += weapon.cooldown_reset;
-
-                // Spawn bullets
-                let mut position_bullet_left = *position;
-                let left_x = position_bullet_left.x() - POS_OFFSET;
-                position_bullet_left.reset_x(left_x);
-
-                let mut position_bullet_right = *position;
-                let right_x = position_bullet_right.x() + POS_OFFSET;
-                position_bullet_right.reset_x(right_x);
-
-                PlayerWeaponSystem::spawn_bullet(
-                    lazy_update.create_entity(&entities),
-                    position_bullet_left,
-                    weapon,
-                );
-
-                PlayerWeaponSystem::spawn_bullet(
-                    lazy_update.create_entity(&entities),
-                    position_bullet_right,
-                    weapon,
-                );
-
-                // Animation
-                animation.glow_animation_state = GlowAnimationState::Fire;
-
-                // Audio
-                audio.play_sound(weapon.bullet_sound);
-
-                info!(target: "PlayerWeaponSystem", "Spawn bullets");
+        let (player_input, timing, audio, entities, lazy_update, mut weapon, position) = data;
+        let dt = timing.delta_time.as_secs_f32();
+
+        for (weapon, position) in (&mut weapon, &position).join() {
+            weapon.fired = false;
+
+            // The remainder of the cooldown carries over, so the fire rate doesn't depend on the
+            // tick rate. A weapon ready to fire doesn't count down any further.
+            if weapon.cooldown > 0.0 {
+                weapon.cooldown -= dt;
             }
+            if weapon.cooldown > TIME_TOLERANCE {
+                continue;
+            }
+            if !player_input.shoot_air {
+                // Ready to fire, without saving up shots
+                weapon.cooldown = 0.0;
+                continue;
+            }
+
+            // Only time actually spent beyond the cooldown carries over, not the rounding errors
+            // of the tick duration, which would add up while the trigger is held
+            weapon.cooldown = weapon.cooldown_reset + overshoot(weapon.cooldown);
+
+            // Spawn bullets
+            let mut position_bullet_left = *position;
+            let left_x = position_bullet_left.x() - POS_OFFSET;
+            position_bullet_left.reset_x(left_x);
+
+            let mut position_bullet_right = *position;
+            let right_x = position_bullet_right.x() + POS_OFFSET;
+            position_bullet_right.reset_x(right_x);
+
+            PlayerWeaponSystem::spawn_bullet(
+                lazy_update.create_entity(&entities),
+                position_bullet_left,
+                weapon,
+            );
+
+            PlayerWeaponSystem::spawn_bullet(
+                lazy_update.create_entity(&entities),
+                position_bullet_right,
+                weapon,
+            );
+
+            weapon.fired = true;
+
+            // Audio
+            audio.play_sound(weapon.bullet_sound);
+
+            info!(target: "PlayerWeaponSystem", "Spawn bullets");
         }
     }
 }
@@ -1,99 +1,778 @@
-use std::time::Instant;
+use std::{
+    ffi::{c_void, CStr},
+    mem,
+    os::raw::c_int,
+    ptr,
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
 
 use log::{trace, warn};
 use sdl2::{
-    pixels::Color,
-    render::{Canvas, RenderTarget},
+    gfx::primitives::DrawRenderer,
+    pixels::{Color, PixelFormatEnum},
+    rect::{Point, Rect},
+    render::{BlendMode, Canvas, RenderTarget, Texture},
+    surface::Surface,
+    video::{FullscreenType, Window, WindowPos},
 };
-use specs::{Join, Read, ReadStorage, System};
+use serde::Deserialize;
+use specs::{Entities, Entity, Join, Read, ReadStorage, System, Write};
 
 use crate::{
-    component::{position::PositionComponent, sprite::SpriteComponent},
-    resource::timing::Timing,
+    component::{
+        hitbox::{HitboxComponent, HitboxShape},
+        particle_emitter::ParticleEmitterComponent,
+        position::PositionComponent,
+        sprite::{SpriteBlendMode, SpriteComponent},
+        text::TextComponent,
+    },
+    errors::SdlError,
+    font::{Alignment, BitmapFont},
+    hud::Hud,
+    resource::{
+        background::{Background, BackgroundPlane},
+        debug::{DebugOverlay, FRAME_HISTORY},
+        display::{Display, WindowMode},
+        game_state::GameState,
+        screenshot::{self, Resolution, ScreenshotRequest, Screenshots},
+        timing::Timing,
+    },
     sprite::SpriteManager,
-    WINDOW_SCALE, // FIXME: Proper handling of window size?
+    FRAME_RATE_RENDER, GAME_HEIGHT, GAME_WIDTH,
 };
 
+/// Outline of sprite frames in the debug overlay
+const DEBUG_FRAME_COLOR: Color = Color::RGB(0, 255, 0);
+/// Outline of hitboxes in the debug overlay
+const DEBUG_HITBOX_COLOR: Color = Color::RGB(255, 0, 0);
+
+/// Height of the frame time graph in window pixels, and the frame time it corresponds to
+const GRAPH_HEIGHT: u32 = 100;
+const GRAPH_MAX_FRAME_TIME: Duration = Duration::from_millis(50);
+/// Width of a single frame in the frame time graph in window pixels
+const GRAPH_BAR_WIDTH: u32 = 2;
+
+/// Render target the game is presented on
+pub trait Screen: RenderTarget + Sized {
+    /// Switch the window mode and set the size of the window, if the target has a window
+    fn set_window(
+        _canvas: &mut Canvas<Self>,
+        _mode: WindowMode,
+        _size: (u32, u32),
+    ) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+impl Screen for Window {
+    fn set_window(
+        canvas: &mut Canvas<Self>,
+        mode: WindowMode,
+        size: (u32, u32),
+    ) -> Result<(), String> {
+        let window = canvas.window_mut();
+        match mode {
+            WindowMode::Windowed => {
+                window.set_fullscreen(FullscreenType::Off)?;
+                window.set_size(size.0, size.1).map_err(|e| e.to_string())?;
+                window.set_position(WindowPos::Centered, WindowPos::Centered);
+            }
+            WindowMode::DesktopFullscreen => window.set_fullscreen(FullscreenType::Desktop)?,
+            WindowMode::Fullscreen => {
+                // The window size becomes the display resolution
+                window.set_size(size.0, size.1).map_err(|e| e.to_string())?;
+                window.set_fullscreen(FullscreenType::True)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Screen for Surface<'_> {}
+
+/// `SDL_RenderSetVSync`, not covered by the bindings of sdl2-sys
+type RenderSetVSync = unsafe extern "C" fn(*mut sdl2::sys::SDL_Renderer, c_int) -> c_int;
+
+/// Library `SDL_RenderSetVSync` is looked up in, the program and its libraries without one
+#[cfg(windows)]
+const SDL_LIBRARY: Option<&CStr> = Some(c"SDL2.dll");
+#[cfg(not(windows))]
+const SDL_LIBRARY: Option<&CStr> = None;
+
+/// `SDL_RenderSetVSync` of the loaded SDL, which lacks it before 2.0.18
+fn render_set_vsync() -> Option<RenderSetVSync> {
+    static FUNCTION: OnceLock<Option<RenderSetVSync>> = OnceLock::new();
+
+    *FUNCTION.get_or_init(|| {
+        // SAFETY: the library handle is never unloaded, the function has the signature of
+        // SDL_render.h
+        unsafe {
+            let library = sdl2::sys::SDL_LoadObject(SDL_LIBRARY.map_or(ptr::null(), CStr::as_ptr));
+            if library.is_null() {
+                return None;
+            }
+            let function = sdl2::sys::SDL_LoadFunction(library, c"SDL_RenderSetVSync".as_ptr());
+            (!function.is_null()).then(|| mem::transmute::<*mut c_void, RenderSetVSync>(function))
+        }
+    })
+}
+
+/// Whether vsync can be switched while running, needs SDL 2.0.18 or newer
+pub fn vsync_switchable() -> bool {
+    render_set_vsync().is_some()
+}
+
+/// Switch vsync of a renderer, keeping its textures
+fn set_vsync<T: RenderTarget>(canvas: &Canvas<T>, vsync: bool) -> Result<(), String> {
+    let Some(render_set_vsync) = render_set_vsync() else {
+        return Err("SDL_RenderSetVSync is not available".to_string());
+    };
+
+    // SAFETY: the renderer is alive as long as the canvas
+    match unsafe { render_set_vsync(canvas.raw(), vsync as c_int) } {
+        0 => Ok(()),
+        _ => Err(sdl2::get_error()),
+    }
+}
+
 /// Render layer
-/// Sprites are rendered according to their associated layer (lower enum value = background)
-#[derive(PartialEq, Eq)]
+/// Sprites are rendered according to their associated layer (lower enum value = background),
+/// within a layer according to their z-order. Background planes are rendered before all sprites.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
 pub enum Layer {
-    /// Explosions, bullets, etc.
-    Effects,
+    /// Sprites on top of the background planes
+    Background,
+    /// Ground structures, e.g. buildings
+    Ground,
+    /// Tanks, turrets, etc.
+    GroundUnits,
+    /// Shadows of air units
+    Shadows,
+    /// Explosions on the ground, craters, etc.
+    GroundEffects,
+    /// Bullets of the player and enemies, below the units firing them
+    Bullets,
     /// Player unit, air enemies
     AirUnits,
+    /// Explosions, hit sparks, etc.
+    AirEffects,
+    /// Score, lives, etc.
+    Hud,
+}
+
+/// What a draw command draws
+enum Drawable {
+    /// Sprite of the entity's `SpriteComponent`
+    Sprite(Entity),
+    /// Text of the entity's `TextComponent`
+    Text(Entity),
+    /// All particles of the entity's `ParticleEmitterComponent`, batched
+    Particles(Entity),
+}
+
+/// Sprite or text to be drawn in the current frame
+struct DrawCommand {
+    layer: Layer,
+    z_order: i32,
+    drawable: Drawable,
+    scale_factor: f32,
+    /// Interpolated position of the entity
+    x: f32,
+    y: f32,
 }
 
 pub struct RenderSystem<'t, T>
 where
-    T: RenderTarget,
+    T: Screen,
 {
     canvas: Canvas<T>,
+    /// Texture in game resolution the game world is rendered to before it is scaled to the
+    /// window
+    target: Texture<'t>,
     sprites: SpriteManager<'t>,
+    hud: Hud,
+    /// Display settings currently applied to the window, to detect changes
+    display: Display,
+    /// Kept between frames to avoid reallocating it every frame
+    draw_commands: Vec<DrawCommand>,
 }
 
 impl<'t, T> RenderSystem<'t, T>
 where
-    T: RenderTarget,
+    T: Screen,
 {
-    pub fn new(canvas: Canvas<T>, sprite_manager: SpriteManager<'t>) -> Self {
+    /// Create a render system drawing the game world into `target`, which has to be a render
+    /// target texture in game resolution
+    ///
+    /// `display` are the settings the window and canvas were created with.
+    pub fn new(
+        canvas: Canvas<T>,
+        target: Texture<'t>,
+        sprite_manager: SpriteManager<'t>,
+        hud: Hud,
+        display: Display,
+    ) -> Self {
         Self {
             canvas,
+            target,
             sprites: sprite_manager,
+            hud,
+            display,
+            draw_commands: Vec::new(),
         }
     }
 
-    fn render_layer(
-        &mut self,
+    /// Render a background plane, the bottom row starts at the bottom of the screen
+    fn render_plane(
+        canvas: &mut Canvas<T>,
+        sprites: &SpriteManager<'t>,
+        plane: &BackgroundPlane,
+        alpha: f32,
+    ) {
+        let sprite_ref = sprites.get(plane.sprite);
+        let (tile_width, tile_height) = (plane.tile_size.0 as i32, plane.tile_size.1 as i32);
+        let map_height = plane.height() as f32;
+
+        let top = GAME_HEIGHT as f32 - map_height + plane.interpolated_offset(alpha);
+        let (mut top, copies) = if plane.repeat {
+            // Enough copies of the map to cover the screen, starting just above it
+            let top = top.rem_euclid(map_height) - map_height;
+            (top, (GAME_HEIGHT as f32 / map_height).ceil() as usize + 1)
+        } else {
+            (top, 1)
+        };
+
+        for _ in 0..copies {
+            let map_top = top.round() as i32;
+
+            for (row_idx, row) in plane.tiles.iter().enumerate() {
+                let y = map_top + row_idx as i32 * tile_height;
+                if y + tile_height <= 0 || y >= GAME_HEIGHT as i32 {
+                    continue;
+                }
+
+                for (column_idx, frame) in row.iter().enumerate() {
+                    let Some(frame) = frame else {
+                        continue;
+                    };
+
+                    canvas
+                        .copy(
+                            sprite_ref.texture(),
+                            sprite_ref.get_rect_of_frame(*frame),
+                            Rect::new(
+                                column_idx as i32 * tile_width,
+                                y,
+                                tile_width as u32,
+                                tile_height as u32,
+                            ),
+                        )
+                        .unwrap(); // FIXME
+                }
+            }
+
+            top += map_height;
+        }
+    }
+
+    /// Render the HUD in window coordinates inside the viewport of the game, independent of the
+    /// game resolution
+    fn render_hud(
+        canvas: &mut Canvas<T>,
+        sprites: &mut SpriteManager<'t>,
+        hud: &Hud,
+        game_state: &GameState,
+        viewport: Rect,
+    ) {
+        let scale = hud.scale;
+        let font = &hud.font;
+
+        for element in &hud.elements {
+            let text = element.text(game_state);
+            let (width, height) = font.measure(&text);
+            let text_size = (width as u32 * scale, height as u32 * scale);
+            let (x, y) = element.position(viewport.size(), text_size);
+
+            Self::render_text(
+                canvas,
+                sprites,
+                font,
+                &text,
+                Alignment::Left,
+                (viewport.x() + x, viewport.y() + y),
+                scale as f32,
+                element.color,
+            );
+        }
+    }
+
+    /// Render `text` in canvas coordinates, `position` is laid out as by `BitmapFont::layout`
+    #[allow(clippy::too_many_arguments)]
+    fn render_text(
+        canvas: &mut Canvas<T>,
+        sprites: &mut SpriteManager<'t>,
+        font: &BitmapFont,
+        text: &str,
+        alignment: Alignment,
+        position: (i32, i32),
+        scale: f32,
+        color: (u8, u8, u8),
+    ) {
+        let sprite_ref = sprites.get_mut(font.sprite());
+        let (width, height) = font.glyph_size();
+        let (r, g, b) = color;
+
+        sprite_ref.texture_mut().set_color_mod(r, g, b);
+        for glyph in font.layout(text, alignment) {
+            canvas
+                .copy(
+                    sprite_ref.texture(),
+                    sprite_ref.get_rect_of_frame(glyph.frame),
+                    Rect::new(
+                        position.0 + (glyph.x as f32 * scale).round() as i32,
+                        position.1 + (glyph.y as f32 * scale).round() as i32,
+                        (width as f32 * scale).round() as u32,
+                        (height as f32 * scale).round() as u32,
+                    ),
+                )
+                .unwrap(); // FIXME
+        }
+        sprite_ref.texture_mut().set_color_mod(255, 255, 255);
+    }
+
+    /// Render all sprites and texts in a single pass, sorted by layer and z-order
+    fn render_sprites(
+        canvas: &mut Canvas<T>,
+        sprites: &mut SpriteManager<'t>,
+        draw_commands: &mut Vec<DrawCommand>,
         system_data: &<RenderSystem<'t, T> as System>::SystemData,
         alpha: f32,
-        layer: Layer,
     ) {
-        let (sprite, position, _) = system_data;
+        let (entities, sprite, text, particles, position, _, _, _, _, _, _, _) = system_data;
+        let interpolate = |position: &PositionComponent| {
+            (
+                position.x() * alpha + position.previous_x() * (1.0 - alpha),
+                position.y() * alpha + position.previous_y() * (1.0 - alpha),
+            )
+        };
 
-        for (sprite, position) in (sprite, position)
-            .join()
-            .filter(|(sprite, _)| sprite.layer == layer)
-        {
-            let sprite_ref = self.sprites.get(sprite.sprite);
+        draw_commands.clear();
+        draw_commands.extend((entities, sprite, position).join().map(
+            |(entity, sprite, position)| {
+                let (x, y) = interpolate(position);
+                DrawCommand {
+                    layer: sprite.layer,
+                    z_order: sprite.z_order,
+                    drawable: Drawable::Sprite(entity),
+                    scale_factor: sprite.scale_factor,
+                    x,
+                    y,
+                }
+            },
+        ));
+        draw_commands.extend(
+            (entities, text, position)
+                .join()
+                .map(|(entity, text, position)| {
+                    let (x, y) = interpolate(position);
+                    DrawCommand {
+                        layer: text.layer,
+                        z_order: text.z_order,
+                        drawable: Drawable::Text(entity),
+                        scale_factor: text.scale_factor,
+                        x,
+                        y,
+                    }
+                }),
+        );
+        draw_commands.extend((entities, particles).join().map(|(entity, particles)| {
+            let emitter = &particles.emitter.definition;
+            DrawCommand {
+                layer: emitter.layer,
+                z_order: emitter.z_order,
+                drawable: Drawable::Particles(entity),
+                // Particles have their own position and size
+                scale_factor: 1.0,
+                x: 0.0,
+                y: 0.0,
+            }
+        }));
+        // Stable, so sprites with equal layer and z-order keep the (deterministic) join order
+        draw_commands.sort_by_key(|command| (command.layer, command.z_order));
+
+        for command in draw_commands.iter() {
+            let (x, y, scale_factor) = (command.x, command.y, command.scale_factor);
+
+            match command.drawable {
+                Drawable::Sprite(entity) => {
+                    let sprite = sprite
+                        .get(entity)
+                        .expect("sprite entity should have a sprite");
+
+                    Self::render_sprite(canvas, sprites, sprite, (x, y), alpha);
+                }
+                Drawable::Text(entity) => {
+                    let text = text.get(entity).expect("text entity should have a text");
+                    let height = text.font.measure(&text.text).1 as f32 * scale_factor;
+
+                    Self::render_text(
+                        canvas,
+                        sprites,
+                        &text.font,
+                        &text.text,
+                        text.alignment,
+                        (x.round() as i32, (y - height / 2.0).round() as i32),
+                        scale_factor,
+                        text.color,
+                    );
+                }
+                Drawable::Particles(entity) => {
+                    let particles = particles
+                        .get(entity)
+                        .expect("particle entity should have an emitter");
+
+                    Self::render_particles(canvas, sprites, particles, alpha);
+                }
+            }
+        }
+    }
+
+    /// Render a sprite centered on `position`, rotated, flipped and modulated as set in its
+    /// component
+    fn render_sprite(
+        canvas: &mut Canvas<T>,
+        sprites: &mut SpriteManager<'t>,
+        sprite: &SpriteComponent,
+        (x, y): (f32, f32),
+        alpha: f32,
+    ) {
+        let sprite_ref = sprites.get_mut(sprite.sprite);
+        let width = sprite_ref.frame_width() as f32 * sprite.scale_factor;
+        let height = sprite_ref.frame_height() as f32 * sprite.scale_factor;
+        let pivot = sprite.pivot.map(|(pivot_x, pivot_y)| {
+            Point::new(
+                (pivot_x * sprite.scale_factor).round() as i32,
+                (pivot_y * sprite.scale_factor).round() as i32,
+            )
+        });
+        let (r, g, b, a) = sprite.color;
+        let opacity = sprite.interpolated_opacity(alpha);
 
-            let x = position.x() * alpha + position.previous_x() * (1.0 - alpha);
-            let y = position.y() * alpha + position.previous_y() * (1.0 - alpha);
+        let texture = sprite_ref.texture_mut();
+        texture.set_color_mod(r, g, b);
+        texture.set_alpha_mod((a as f32 * opacity).round() as u8);
+        texture.set_blend_mode(match sprite.blend_mode {
+            SpriteBlendMode::Alpha => BlendMode::Blend,
+            SpriteBlendMode::Additive => BlendMode::Add,
+            SpriteBlendMode::Modulate => BlendMode::Mod,
+        });
 
-            self.canvas
-            .copy(
+        canvas
+            .copy_ex(
                 sprite_ref.texture(),
                 /* FIXME: returning an option here might not be the best idea, since 'None' in this context means "copy the whole source texture" */
                 sprite_ref.get_rect_of_frame(sprite.current_frame_idx),
-                sdl2::rect::Rect::new(
-                    ((x - sprite_ref.frame_width() as f32 * sprite.scale_factor / 2.0) * WINDOW_SCALE as f32).round() as i32,
-                    ((y - sprite_ref.frame_height() as f32 * sprite.scale_factor / 2.0) * WINDOW_SCALE as f32).round() as i32,
-                    (sprite_ref.frame_width() as f32 * sprite.scale_factor * WINDOW_SCALE as f32).round() as u32,
-                    (sprite_ref.frame_height() as f32 * sprite.scale_factor * WINDOW_SCALE as f32).round() as u32,
+                Rect::new(
+                    (x - width / 2.0).round() as i32,
+                    (y - height / 2.0).round() as i32,
+                    width.round() as u32,
+                    height.round() as u32,
                 ),
+                sprite.interpolated_rotation(alpha) as f64,
+                pivot,
+                sprite.flip_horizontal,
+                sprite.flip_vertical,
+            )
+            .unwrap(); // FIXME
+
+        // Textures are shared between all entities with the same sprite
+        let texture = sprite_ref.texture_mut();
+        texture.set_color_mod(255, 255, 255);
+        texture.set_alpha_mod(255);
+        texture.set_blend_mode(BlendMode::Blend);
+    }
+
+    /// Render all particles of an emitter with its sprite, tinted and faded per particle
+    fn render_particles(
+        canvas: &mut Canvas<T>,
+        sprites: &mut SpriteManager<'t>,
+        particles: &ParticleEmitterComponent,
+        alpha: f32,
+    ) {
+        let emitter = &particles.emitter;
+        let sprite_ref = sprites.get_mut(emitter.sprite);
+        let (frame_width, frame_height) = (
+            sprite_ref.frame_width() as f32,
+            sprite_ref.frame_height() as f32,
+        );
+
+        for particle in &particles.particles {
+            let x = particle.x * alpha + particle.previous_x * (1.0 - alpha);
+            let y = particle.y * alpha + particle.previous_y * (1.0 - alpha);
+            let size = emitter.size(particle);
+            let (width, height) = (frame_width * size, frame_height * size);
+            let (r, g, b) = emitter.color(particle);
+
+            let texture = sprite_ref.texture_mut();
+            texture.set_color_mod(r, g, b);
+            texture.set_alpha_mod(emitter.alpha(particle));
+
+            canvas
+                .copy(
+                    sprite_ref.texture(),
+                    sprite_ref.get_rect_of_frame(emitter.frame(particle)),
+                    Rect::new(
+                        (x - width / 2.0).round() as i32,
+                        (y - height / 2.0).round() as i32,
+                        (width.round() as u32).max(1),
+                        (height.round() as u32).max(1),
+                    ),
+                )
+                .unwrap(); // FIXME
+        }
+
+        let texture = sprite_ref.texture_mut();
+        texture.set_color_mod(255, 255, 255);
+        texture.set_alpha_mod(255);
+    }
+
+    /// Outline sprite frames and hitboxes in game coordinates
+    fn render_debug_shapes(
+        canvas: &mut Canvas<T>,
+        sprites: &SpriteManager<'t>,
+        system_data: &<RenderSystem<'t, T> as System>::SystemData,
+        alpha: f32,
+    ) {
+        let (_, sprite, _, _, position, hitbox, _, _, _, _, _, _) = system_data;
+        let interpolate = |position: &PositionComponent| {
+            (
+                position.x() * alpha + position.previous_x() * (1.0 - alpha),
+                position.y() * alpha + position.previous_y() * (1.0 - alpha),
             )
+        };
+
+        canvas.set_draw_color(DEBUG_FRAME_COLOR);
+        for (sprite, position) in (sprite, position).join() {
+            let (x, y) = interpolate(position);
+            let sprite_ref = sprites.get(sprite.sprite);
+            let width = sprite_ref.frame_width() as f32 * sprite.scale_factor;
+            let height = sprite_ref.frame_height() as f32 * sprite.scale_factor;
+
+            canvas
+                .draw_rect(Rect::new(
+                    (x - width / 2.0).round() as i32,
+                    (y - height / 2.0).round() as i32,
+                    width.round() as u32,
+                    height.round() as u32,
+                ))
+                .unwrap(); // FIXME
+        }
+
+        canvas.set_draw_color(DEBUG_HITBOX_COLOR);
+        for (hitbox, position) in (hitbox, position).join() {
+            let (x, y) = interpolate(position);
+            let (center_x, center_y) = hitbox.center(x, y);
+
+            match hitbox.shape {
+                HitboxShape::Aabb { width, height } => canvas.draw_rect(Rect::new(
+                    (center_x - width / 2.0).round() as i32,
+                    (center_y - height / 2.0).round() as i32,
+                    width.round() as u32,
+                    height.round() as u32,
+                )),
+                HitboxShape::Circle { radius } => canvas.circle(
+                    center_x.round() as i16,
+                    center_y.round() as i16,
+                    radius.round() as i16,
+                    DEBUG_HITBOX_COLOR,
+                ),
+            }
             .unwrap(); // FIXME
         }
     }
+
+    /// Render timing statistics, entity counts and the frame time graph in window coordinates
+    fn render_debug_overlay(
+        canvas: &mut Canvas<T>,
+        sprites: &mut SpriteManager<'t>,
+        hud: &Hud,
+        overlay: &DebugOverlay,
+        viewport: Rect,
+    ) {
+        let scale = hud.scale as i32;
+        let (_, glyph_height) = hud.font.glyph_size();
+        let line_height = (glyph_height as i32 + 2) * scale;
+        let left = viewport.x() + 16;
+        let top = viewport.y() + 16 + 2 * line_height;
+
+        let mut lines = vec![
+            format!("FPS {}", overlay.frame_rate()),
+            format!("TICKS/S {}", overlay.tick_rate()),
+            format!("MISSED {}", overlay.missed_updates),
+        ];
+        lines.extend(
+            overlay
+                .entity_counts
+                .iter()
+                .map(|(name, count)| format!("{} {}", name, count)),
+        );
+        for (line_idx, line) in lines.iter().enumerate() {
+            Self::render_text(
+                canvas,
+                sprites,
+                &hud.font,
+                line,
+                Alignment::Left,
+                (left, top + line_idx as i32 * line_height),
+                scale as f32,
+                (255, 255, 0),
+            );
+        }
+
+        // Frame time graph, newest frame on the right, red bars missed the frame rate
+        let graph_width = FRAME_HISTORY as u32 * GRAPH_BAR_WIDTH;
+        let graph_left = viewport.right() - 16 - graph_width as i32;
+        let graph_bottom = top + GRAPH_HEIGHT as i32;
+        let budget = Duration::from_nanos(1_000_000_000u64 / FRAME_RATE_RENDER as u64);
+        let bar_height = |frame_time: Duration| {
+            let frame_time = frame_time.min(GRAPH_MAX_FRAME_TIME);
+            (frame_time.as_secs_f32() / GRAPH_MAX_FRAME_TIME.as_secs_f32() * GRAPH_HEIGHT as f32)
+                .round() as u32
+        };
+
+        canvas.set_draw_color(Color::RGB(32, 32, 32));
+        canvas
+            .fill_rect(Rect::new(graph_left, top, graph_width, GRAPH_HEIGHT))
+            .unwrap(); // FIXME
+
+        let frame_times: Vec<_> = overlay.frame_times().collect();
+        let first_bar = FRAME_HISTORY - frame_times.len();
+        for (bar_idx, frame_time) in frame_times.into_iter().enumerate() {
+            let height = bar_height(frame_time).max(1);
+            // Allow for some jitter of the frame start
+            let color = if frame_time.as_secs_f32() > budget.as_secs_f32() * 1.5 {
+                Color::RGB(255, 0, 0)
+            } else {
+                Color::RGB(0, 255, 0)
+            };
+
+            canvas.set_draw_color(color);
+            canvas
+                .fill_rect(Rect::new(
+                    graph_left + ((first_bar + bar_idx) as u32 * GRAPH_BAR_WIDTH) as i32,
+                    graph_bottom - height as i32,
+                    GRAPH_BAR_WIDTH,
+                    height,
+                ))
+                .unwrap(); // FIXME
+        }
+
+        // Frame time of the target frame rate
+        let budget_y = graph_bottom - bar_height(budget) as i32;
+        canvas.set_draw_color(Color::RGB(255, 255, 255));
+        canvas
+            .draw_line(
+                (graph_left, budget_y),
+                (graph_left + graph_width as i32 - 1, budget_y),
+            )
+            .unwrap(); // FIXME
+    }
+
+    /// Write the current frame to the PNG file of `request`
+    ///
+    /// Native screenshots get the HUD (and the debug overlay, if enabled) drawn into the game
+    /// world, laid out as in a window of the game resolution.
+    fn capture(
+        &mut self,
+        request: &ScreenshotRequest,
+        game_state: &GameState,
+        overlay: &DebugOverlay,
+    ) -> Result<()> {
+        let format = PixelFormatEnum::RGB24;
+        let ((width, height), pixels) = match request.resolution {
+            Resolution::Window => (
+                self.canvas
+                    .output_size()
+                    .map_err(SdlError::ScreenshotError)?,
+                self.canvas.read_pixels(None, format),
+            ),
+            Resolution::Native => {
+                let Self {
+                    canvas,
+                    target,
+                    sprites,
+                    hud,
+                    ..
+                } = self;
+                let mut pixels = Ok(Vec::new());
+                canvas.with_texture_canvas(target, |canvas| {
+                    // The game world was copied to the window already, the HUD is only added
+                    // for the screenshot
+                    let viewport = Rect::new(0, 0, GAME_WIDTH, GAME_HEIGHT);
+                    Self::render_hud(canvas, sprites, hud, game_state, viewport);
+                    if overlay.enabled {
+                        Self::render_debug_overlay(canvas, sprites, hud, overlay, viewport);
+                    }
+
+                    pixels = canvas.read_pixels(None, format);
+                })?;
+                ((GAME_WIDTH, GAME_HEIGHT), pixels)
+            }
+        };
+        let mut pixels = pixels.map_err(SdlError::ScreenshotError)?;
+
+        screenshot::save_png(&request.path, &mut pixels, width, height)
+    }
+
+    /// Apply changed display settings to the window, textures are kept
+    fn apply_display(&mut self, display: &Display) {
+        if display.mode != self.display.mode
+            || (display.mode != WindowMode::DesktopFullscreen
+                && display.window_scale != self.display.window_scale)
+        {
+            if let Err(e) = T::set_window(&mut self.canvas, display.mode, display.window_size()) {
+                warn!(target: "RenderSystem", "Failed to change window: {}", e);
+            }
+        }
+
+        if display.vsync != self.display.vsync {
+            if let Err(e) = set_vsync(&self.canvas, display.vsync) {
+                warn!(target: "RenderSystem", "Failed to switch vsync: {}", e);
+            }
+        }
+
+        // Failed changes are not retried every frame
+        self.display = display.clone();
+    }
 }
 
 impl<'sys, 't, T> System<'sys> for RenderSystem<'t, T>
 where
-    T: RenderTarget,
+    T: Screen,
 {
     type SystemData = (
+        Entities<'sys>,
         ReadStorage<'sys, SpriteComponent>,
+        ReadStorage<'sys, TextComponent>,
+        ReadStorage<'sys, ParticleEmitterComponent>,
         ReadStorage<'sys, PositionComponent>,
+        ReadStorage<'sys, HitboxComponent>,
         Read<'sys, Timing>,
+        Read<'sys, Background>,
+        Read<'sys, GameState>,
+        Read<'sys, Display>,
+        Read<'sys, DebugOverlay>,
+        Write<'sys, Screenshots>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        self.canvas.set_draw_color(Color::RGB(0, 100, 200));
-        self.canvas.clear();
-
         let mut alpha;
         {
-            let (_, _, timing) = &data;
+            let (_, _, _, _, _, _, timing, _, _, _, _, _) = &data;
             let interp_time = match timing.next_vsync {
                 Some(next_vsync) => next_vsync,
                 None => Instant::now(),
@@ -115,11 +794,69 @@ where
             );
         }
 
-        // Render effects
-        self.render_layer(&data, alpha, Layer::Effects);
+        let (_, _, _, _, _, _, _, background, game_state, display, overlay, _) = &data;
+        self.apply_display(display);
 
-        // Render player, air enemies
-        self.render_layer(&data, alpha, Layer::AirUnits);
+        // Render the game world in game resolution
+        let Self {
+            canvas,
+            target,
+            sprites,
+            draw_commands,
+            ..
+        } = self;
+        canvas
+            .with_texture_canvas(target, |canvas| {
+                // Background planes, furthest away first
+                let (r, g, b) = background.color;
+                canvas.set_draw_color(Color::RGB(r, g, b));
+                canvas.clear();
+
+                for plane in &background.planes {
+                    Self::render_plane(canvas, sprites, plane, alpha);
+                }
+
+                // Sprites and texts of all layers, from the ground up
+                Self::render_sprites(canvas, sprites, draw_commands, &data, alpha);
+
+                if overlay.enabled {
+                    Self::render_debug_shapes(canvas, sprites, &data, alpha);
+                }
+            })
+            .unwrap(); // FIXME
+
+        // Scale the game world to the window, the rest of the window stays black
+        let viewport = display.scaling.viewport(self.canvas.output_size().unwrap()); // FIXME
+        self.canvas.set_draw_color(Color::RGB(0, 0, 0));
+        self.canvas.clear();
+        self.canvas.copy(&self.target, None, viewport).unwrap(); // FIXME
+
+        // Render HUD on top of the game world
+        Self::render_hud(
+            &mut self.canvas,
+            &mut self.sprites,
+            &self.hud,
+            game_state,
+            viewport,
+        );
+
+        if overlay.enabled {
+            Self::render_debug_overlay(
+                &mut self.canvas,
+                &mut self.sprites,
+                &self.hud,
+                overlay,
+                viewport,
+            );
+        }
+
+        // Screenshots have to be read back before presenting, the window content is undefined
+        // afterwards
+        let (_, _, _, _, _, _, _, _, game_state, _, overlay, mut screenshots) = data;
+        for request in screenshots.take_pending() {
+            let result = self.capture(&request, &game_state, &overlay);
+            screenshots.finish(request.path, result);
+        }
 
         self.canvas.present();
     }
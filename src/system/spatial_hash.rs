@@ -0,0 +1,32 @@
+use specs::{Entities, Join, ReadStorage, System, Write};
+
+use crate::{
+    component::{hitbox::HitboxComponent, position::PositionComponent},
+    resource::spatial_hash::SpatialHash,
+};
+
+/// Rebuilds the spatial hash from the final positions of this tick
+pub struct SpatialHashSystem;
+
+impl<'sys> System<'sys> for SpatialHashSystem {
+    type SystemData = (
+        Entities<'sys>,
+        Write<'sys, SpatialHash>,
+        ReadStorage<'sys, HitboxComponent>,
+        ReadStorage<'sys, PositionComponent>,
+    );
+
+    fn run(&mut self, data: Self::SystemData) {
+        let (entities, mut spatial_hash, hitbox, position) = data;
+
+        spatial_hash.clear();
+
+        for (entity, hitbox, position) in (&entities, &hitbox, &position).join() {
+            spatial_hash.insert(
+                entity,
+                hitbox.layer,
+                hitbox.bounds(position.x(), position.y()),
+            );
+        }
+    }
+}
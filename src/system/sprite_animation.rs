@@ -0,0 +1,26 @@
+use specs::{Join, Read, System, WriteStorage};
+
+use crate::{
+    component::{sprite::SpriteComponent, sprite_animation::SpriteAnimationComponent},
+    resource::timing::Timing,
+};
+
+/// Advances sprite animations and applies their current frame to the sprite
+pub struct SpriteAnimationSystem;
+
+impl<'sys> System<'sys> for SpriteAnimationSystem {
+    type SystemData = (
+        Read<'sys, Timing>,
+        WriteStorage<'sys, SpriteAnimationComponent>,
+        WriteStorage<'sys, SpriteComponent>,
+    );
+
+    fn run(&mut self, (timing, mut animation, mut sprite): Self::SystemData) {
+        let dt = timing.delta_time.as_secs_f32();
+
+        for (animation, sprite) in (&mut animation, &mut sprite).join() {
+            animation.advance(dt);
+            sprite.current_frame_idx = animation.current_frame();
+        }
+    }
+}
@@ -0,0 +1,748 @@
+use std::{collections::HashMap, fs, path::Path, str::FromStr};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{de::IgnoredAny, Deserialize};
+
+use crate::{
+    level::{BackgroundDefinition, Level, LevelEvent, PlaneDefinition, TimelineEntry, Trigger},
+    resource::background::Background,
+    sprite::SpriteDescription,
+    GAME_HEIGHT,
+};
+
+/// Tile ids with any of these bits set are flipped or rotated
+const GID_FLAG_MASK: u32 = 0xf000_0000;
+
+/// Custom property value of a map, layer, tileset or object
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum Property {
+    Bool(bool),
+    Number(f64),
+    String(String),
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonProperty {
+    name: String,
+    value: Property,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(from = "Vec<JsonProperty>")]
+struct Properties(HashMap<String, Property>);
+
+impl From<Vec<JsonProperty>> for Properties {
+    fn from(properties: Vec<JsonProperty>) -> Self {
+        Self(
+            properties
+                .into_iter()
+                .map(|property| (property.name, property.value))
+                .collect(),
+        )
+    }
+}
+
+impl Properties {
+    fn string(&self, name: &str) -> Result<Option<&str>> {
+        match self.0.get(name) {
+            Some(Property::String(value)) => Ok(Some(value)),
+            Some(_) => bail!("property \"{}\" must be a string", name),
+            None => Ok(None),
+        }
+    }
+
+    fn number(&self, name: &str) -> Result<Option<f32>> {
+        match self.0.get(name) {
+            Some(Property::Number(value)) => Ok(Some(*value as f32)),
+            Some(_) => bail!("property \"{}\" must be a number", name),
+            None => Ok(None),
+        }
+    }
+
+    fn bool(&self, name: &str) -> Result<Option<bool>> {
+        match self.0.get(name) {
+            Some(Property::Bool(value)) => Ok(Some(*value)),
+            Some(_) => bail!("property \"{}\" must be a bool", name),
+            None => Ok(None),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Tileset {
+    #[serde(rename = "firstgid")]
+    first_gid: u32,
+    /// External tileset file, not supported
+    #[serde(default)]
+    source: Option<String>,
+    #[serde(default)]
+    name: String,
+    #[serde(default, rename = "tilewidth")]
+    tile_width: usize,
+    #[serde(default, rename = "tileheight")]
+    tile_height: usize,
+    #[serde(default, rename = "tilecount")]
+    tile_count: usize,
+    #[serde(default)]
+    columns: usize,
+    #[serde(default)]
+    margin: usize,
+    #[serde(default)]
+    spacing: usize,
+}
+
+impl Tileset {
+    /// Frame slicing of the tileset image, which has to be a single row of tiles
+    fn sprite_description(&self) -> Result<SpriteDescription> {
+        if let Some(source) = &self.source {
+            bail!(
+                "external tileset {} is not supported, embed it in the map",
+                source
+            );
+        }
+        if self.columns != self.tile_count {
+            bail!("tileset \"{}\" must be a single row of tiles", self.name);
+        }
+        if self.margin != self.spacing {
+            bail!(
+                "tileset \"{}\" must use the same margin and spacing",
+                self.name
+            );
+        }
+
+        Ok(SpriteDescription {
+            number_of_frames: self.tile_count,
+            border_left: self.spacing,
+            border_up: self.margin,
+            frame_dimensions: (self.tile_width, self.tile_height),
+        })
+    }
+}
+
+/// Tile ids of a layer, only uncompressed CSV data is supported
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum LayerData {
+    Tiles(Vec<u32>),
+    Encoded(IgnoredAny),
+}
+
+#[derive(Debug, Deserialize)]
+struct MapObject {
+    id: u32,
+    /// Event type, named "class" since Tiled 1.9
+    #[serde(default, alias = "class")]
+    r#type: String,
+    x: f32,
+    y: f32,
+    #[serde(default)]
+    width: f32,
+    #[serde(default)]
+    height: f32,
+    #[serde(default)]
+    properties: Properties,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum MapLayer {
+    TileLayer {
+        name: String,
+        #[serde(default = "default_visible")]
+        visible: bool,
+        #[serde(default = "default_parallax", rename = "parallaxy")]
+        parallax_y: f32,
+        #[serde(default)]
+        properties: Properties,
+        data: LayerData,
+    },
+    ObjectGroup {
+        name: String,
+        #[serde(default = "default_visible")]
+        visible: bool,
+        objects: Vec<MapObject>,
+    },
+    #[serde(other)]
+    Unsupported,
+}
+
+fn default_visible() -> bool {
+    true
+}
+
+fn default_parallax() -> f32 {
+    1.0
+}
+
+/// Map as saved by the Tiled editor, either as JSON or as TMX
+#[derive(Debug, Deserialize)]
+struct TiledMap {
+    orientation: String,
+    width: usize,
+    height: usize,
+    #[serde(rename = "tileheight")]
+    tile_height: usize,
+    #[serde(default)]
+    infinite: bool,
+    #[serde(default, rename = "backgroundcolor")]
+    background_color: Option<String>,
+    #[serde(default)]
+    properties: Properties,
+    tilesets: Vec<Tileset>,
+    layers: Vec<MapLayer>,
+}
+
+/// Load a level from a map in Tiled's JSON format
+pub fn load_json<P: AsRef<Path>>(file: P) -> Result<Level> {
+    let file = file.as_ref();
+    let content = fs::read_to_string(file)
+        .with_context(|| format!("Failed to read map file {}", file.display()))?;
+
+    let map: TiledMap = serde_json::from_str(&content)
+        .map_err(|e| anyhow!("{}:{}:{}: {}", file.display(), e.line(), e.column(), e))?;
+
+    map.into_level(file)
+        .with_context(|| format!("Invalid map file {}", file.display()))
+}
+
+/// Load a level from a map in Tiled's TMX format
+pub fn load_tmx<P: AsRef<Path>>(file: P) -> Result<Level> {
+    let file = file.as_ref();
+    let content = fs::read_to_string(file)
+        .with_context(|| format!("Failed to read map file {}", file.display()))?;
+
+    let document = roxmltree::Document::parse(&content).map_err(|e| {
+        let position = e.pos();
+        anyhow!(
+            "{}:{}:{}: {}",
+            file.display(),
+            position.row,
+            position.col,
+            e
+        )
+    })?;
+
+    TiledMap::from_tmx(document.root_element())
+        .and_then(|map| map.into_level(file))
+        .with_context(|| format!("Invalid map file {}", file.display()))
+}
+
+impl TiledMap {
+    fn from_tmx(map: roxmltree::Node) -> Result<Self> {
+        if !map.has_tag_name("map") {
+            bail!("root element must be <map>");
+        }
+
+        let mut tilesets = Vec::new();
+        let mut layers = Vec::new();
+        for child in map.children().filter(|node| node.is_element()) {
+            match child.tag_name().name() {
+                "tileset" => tilesets.push(Tileset {
+                    first_gid: attribute(child, "firstgid")?,
+                    source: child.attribute("source").map(str::to_string),
+                    name: child.attribute("name").unwrap_or_default().to_string(),
+                    tile_width: attribute_or(child, "tilewidth", 0)?,
+                    tile_height: attribute_or(child, "tileheight", 0)?,
+                    tile_count: attribute_or(child, "tilecount", 0)?,
+                    columns: attribute_or(child, "columns", 0)?,
+                    margin: attribute_or(child, "margin", 0)?,
+                    spacing: attribute_or(child, "spacing", 0)?,
+                }),
+                "layer" => layers.push(MapLayer::TileLayer {
+                    name: child.attribute("name").unwrap_or_default().to_string(),
+                    visible: attribute_or(child, "visible", 1)? != 0,
+                    parallax_y: attribute_or(child, "parallaxy", 1.0)?,
+                    properties: tmx_properties(child)?,
+                    data: tmx_layer_data(child)?,
+                }),
+                "objectgroup" => layers.push(MapLayer::ObjectGroup {
+                    name: child.attribute("name").unwrap_or_default().to_string(),
+                    visible: attribute_or(child, "visible", 1)? != 0,
+                    objects: child
+                        .children()
+                        .filter(|node| node.has_tag_name("object"))
+                        .map(|object| {
+                            Ok(MapObject {
+                                id: attribute(object, "id")?,
+                                r#type: object
+                                    .attribute("type")
+                                    .or(object.attribute("class"))
+                                    .unwrap_or_default()
+                                    .to_string(),
+                                x: attribute(object, "x")?,
+                                y: attribute(object, "y")?,
+                                width: attribute_or(object, "width", 0.0)?,
+                                height: attribute_or(object, "height", 0.0)?,
+                                properties: tmx_properties(object)?,
+                            })
+                        })
+                        .collect::<Result<_>>()?,
+                }),
+                "imagelayer" | "group" => layers.push(MapLayer::Unsupported),
+                _ => (),
+            }
+        }
+
+        Ok(Self {
+            orientation: map.attribute("orientation").unwrap_or_default().to_string(),
+            width: attribute(map, "width")?,
+            height: attribute(map, "height")?,
+            tile_height: attribute(map, "tileheight")?,
+            infinite: attribute_or(map, "infinite", 0)? != 0,
+            background_color: map.attribute("backgroundcolor").map(str::to_string),
+            properties: tmx_properties(map)?,
+            tilesets,
+            layers,
+        })
+    }
+
+    /// Convert the map to a level, `file` provides the name if the map has no "name" property
+    ///
+    /// Tile layers become background planes, using the catalog sprite named like their tileset.
+    /// The tileset image is not loaded, the sprite has to match its slicing.
+    /// Objects of object layers become events, triggered once the map has scrolled far enough
+    /// for them to reach the top of the screen.
+    fn into_level(self, file: &Path) -> Result<Level> {
+        if self.orientation != "orthogonal" {
+            bail!("only orthogonal maps are supported");
+        }
+        if self.infinite {
+            bail!("infinite maps are not supported");
+        }
+
+        let name = match self.properties.string("name")? {
+            Some(name) => name.to_string(),
+            None => file
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+        };
+        let scroll_speed = self.properties.number("scroll_speed")?.unwrap_or(0.0);
+        let color = match &self.background_color {
+            Some(color) => parse_color(color)?,
+            None => Background::default().color,
+        };
+
+        let mut planes = Vec::new();
+        let mut events = Vec::new();
+        for layer in &self.layers {
+            match layer {
+                MapLayer::TileLayer {
+                    name,
+                    visible: true,
+                    parallax_y,
+                    properties,
+                    data,
+                } => planes.push(
+                    self.plane(*parallax_y, properties, data)
+                        .with_context(|| format!("invalid tile layer \"{}\"", name))?,
+                ),
+                MapLayer::ObjectGroup {
+                    name,
+                    visible: true,
+                    objects,
+                } => {
+                    for object in objects {
+                        events.push(self.event(object).with_context(|| {
+                            format!("invalid object {} in layer \"{}\"", object.id, name)
+                        })?);
+                    }
+                }
+                MapLayer::Unsupported => bail!("only tile and object layers are supported"),
+                _ => (),
+            }
+        }
+
+        // Timeline in the order the events happen while scrolling
+        let distance = |entry: &TimelineEntry| match entry.trigger {
+            Trigger::Distance(distance) => distance,
+            Trigger::Time(_) => 0.0,
+        };
+        events.sort_by(|a, b| distance(a).total_cmp(&distance(b)));
+
+        Ok(Level {
+            name,
+            scroll_speed,
+            background: Some(BackgroundDefinition { color, planes }),
+            events,
+            source: None,
+        })
+    }
+
+    fn plane(
+        &self,
+        parallax: f32,
+        properties: &Properties,
+        data: &LayerData,
+    ) -> Result<PlaneDefinition> {
+        let gids = match data {
+            LayerData::Tiles(gids) => gids,
+            LayerData::Encoded(_) => bail!("only CSV encoded tile data is supported"),
+        };
+        if gids.len() != self.width * self.height {
+            bail!(
+                "layer has {} tiles instead of {}",
+                gids.len(),
+                self.width * self.height
+            );
+        }
+
+        let mut tileset = None;
+        let tiles: Vec<i32> = gids
+            .iter()
+            .map(|&gid| {
+                if gid == 0 {
+                    return Ok(-1);
+                }
+                if gid & GID_FLAG_MASK != 0 {
+                    bail!("flipped or rotated tiles are not supported");
+                }
+
+                let tile_tileset = self
+                    .tilesets
+                    .iter()
+                    .filter(|tileset| tileset.first_gid <= gid)
+                    .max_by_key(|tileset| tileset.first_gid)
+                    .with_context(|| format!("tile {} has no tileset", gid))?;
+                match tileset {
+                    None => tileset = Some(tile_tileset),
+                    Some(tileset) if tileset.first_gid != tile_tileset.first_gid => {
+                        bail!("all tiles of a layer must use the same tileset")
+                    }
+                    _ => (),
+                }
+
+                Ok((gid - tile_tileset.first_gid) as i32)
+            })
+            .collect::<Result<_>>()?;
+        let tileset = tileset.context("layer has no tiles")?;
+
+        Ok(PlaneDefinition {
+            sprite: tileset.name.clone(),
+            parallax,
+            repeat: properties.bool("repeat")?.unwrap_or(true),
+            tiles: tiles.chunks(self.width).map(<[i32]>::to_vec).collect(),
+            slicing: Some(tileset.sprite_description()?),
+        })
+    }
+
+    fn event(&self, object: &MapObject) -> Result<TimelineEntry> {
+        let properties = &object.properties;
+        let required_string = |name| {
+            properties
+                .string(name)?
+                .map(str::to_string)
+                .with_context(|| format!("missing property \"{}\"", name))
+        };
+
+        // Distance until the object reaches the top of the screen, the bottom of the map
+        // starts at the bottom of the screen
+        let map_height = (self.height * self.tile_height) as f32;
+        let (x, y) = (
+            object.x + object.width / 2.0,
+            object.y + object.height / 2.0,
+        );
+        let distance = f32::max(map_height - GAME_HEIGHT as f32 - y, 0.0);
+
+        let event = match object.r#type.as_str() {
+            "Spawn" => LevelEvent::Spawn {
+                enemy: required_string("enemy")?,
+                position: (x, GAME_HEIGHT as f32 - map_height + distance + y),
+                path: properties.string("path")?.map(str::to_string),
+            },
+            "Music" => LevelEvent::Music {
+                file: required_string("file")?,
+            },
+            "BossWarning" => LevelEvent::BossWarning {
+                duration: properties
+                    .number("duration")?
+                    .context("missing property \"duration\"")?,
+            },
+            "EndStage" => LevelEvent::EndStage,
+            kind => bail!("unknown event type \"{}\"", kind),
+        };
+
+        Ok(TimelineEntry {
+            trigger: Trigger::Distance(distance),
+            event,
+        })
+    }
+}
+
+fn attribute<T: FromStr>(node: roxmltree::Node, name: &str) -> Result<T> {
+    let value = node
+        .attribute(name)
+        .with_context(|| format!("<{}> is missing \"{}\"", node.tag_name().name(), name))?;
+    value.parse().map_err(|_| {
+        anyhow!(
+            "invalid \"{}\" of <{}>: {}",
+            name,
+            node.tag_name().name(),
+            value
+        )
+    })
+}
+
+fn attribute_or<T: FromStr>(node: roxmltree::Node, name: &str, default: T) -> Result<T> {
+    match node.attribute(name) {
+        Some(_) => attribute(node, name),
+        None => Ok(default),
+    }
+}
+
+fn tmx_properties(node: roxmltree::Node) -> Result<Properties> {
+    let Some(properties) = node
+        .children()
+        .find(|child| child.has_tag_name("properties"))
+    else {
+        return Ok(Properties::default());
+    };
+
+    properties
+        .children()
+        .filter(|child| child.has_tag_name("property"))
+        .map(|property| {
+            let name: String = attribute(property, "name")?;
+            let value = property
+                .attribute("value")
+                .or(property.text())
+                .unwrap_or_default();
+            let value =
+                match property.attribute("type").unwrap_or("string") {
+                    "bool" => Property::Bool(value == "true"),
+                    "int" | "float" => Property::Number(value.parse().map_err(|_| {
+                        anyhow!("property \"{}\" is not a number: {}", name, value)
+                    })?),
+                    _ => Property::String(value.to_string()),
+                };
+            Ok((name, value))
+        })
+        .collect::<Result<_>>()
+        .map(Properties)
+}
+
+fn tmx_layer_data(layer: roxmltree::Node) -> Result<LayerData> {
+    let data = layer
+        .children()
+        .find(|child| child.has_tag_name("data"))
+        .context("<layer> has no <data>")?;
+
+    match data.attribute("encoding") {
+        Some("csv") => data
+            .text()
+            .unwrap_or_default()
+            .split(',')
+            .map(|gid| {
+                gid.trim()
+                    .parse()
+                    .map_err(|_| anyhow!("invalid tile id: {}", gid.trim()))
+            })
+            .collect::<Result<_>>()
+            .map(LayerData::Tiles),
+        // Deprecated XML format, one <tile> element per tile
+        None => data
+            .children()
+            .filter(|child| child.has_tag_name("tile"))
+            .map(|tile| attribute_or(tile, "gid", 0))
+            .collect::<Result<_>>()
+            .map(LayerData::Tiles),
+        Some(_) => Ok(LayerData::Encoded(IgnoredAny)),
+    }
+}
+
+/// Parse "#rrggbb" or "#aarrggbb", the alpha channel is ignored
+fn parse_color(color: &str) -> Result<(u8, u8, u8)> {
+    let hex = color.trim_start_matches('#');
+    // Also keeps the slicing below on char boundaries
+    if !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        bail!("invalid color: {}", color);
+    }
+    let rgb = match hex.len() {
+        6 => hex,
+        8 => &hex[2..],
+        _ => bail!("invalid color: {}", color),
+    };
+    let value = u32::from_str_radix(rgb, 16).map_err(|_| anyhow!("invalid color: {}", color))?;
+
+    Ok(((value >> 16) as u8, (value >> 8) as u8, value as u8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_tmx(content: &str) -> Result<Level> {
+        let document = roxmltree::Document::parse(content)?;
+        TiledMap::from_tmx(document.root_element())?.into_level(Path::new("test.tmx"))
+    }
+
+    /// 2x1 map with a tile layer of `data` (attributes and content of <data>) and `objects`
+    fn test_map(tilesets: &str, data: &str, objects: &str) -> String {
+        format!(
+            r#"<map orientation="orthogonal" width="2" height="1" tileheight="32">
+                {}
+                <layer name="ground"><data {}</data></layer>
+                <objectgroup name="events">{}</objectgroup>
+            </map>"#,
+            tilesets, data, objects
+        )
+    }
+
+    const TILESET: &str = r#"<tileset firstgid="1" name="tiles" tilewidth="32" tileheight="32" tilecount="2" columns="2"/>"#;
+
+    fn error_message(content: &str) -> String {
+        format!(
+            "{:#}",
+            parse_tmx(content).expect_err("map should be rejected")
+        )
+    }
+
+    fn distance(entry: &TimelineEntry) -> f32 {
+        match entry.trigger {
+            Trigger::Distance(distance) => distance,
+            Trigger::Time(_) => panic!("map events are triggered by distance"),
+        }
+    }
+
+    fn check_demo(level: &Level) {
+        assert_eq!(level.name, "Tiled Demo");
+        assert_eq!(level.scroll_speed, 40.0);
+
+        let background = level.background.as_ref().unwrap();
+        assert_eq!(background.color, (0x22, 0x50, 0x28));
+        assert_eq!(background.planes.len(), 2);
+
+        let terrain = &background.planes[0];
+        assert_eq!(terrain.sprite, "terrain_placeholder");
+        assert_eq!(terrain.parallax, 1.0);
+        assert!(terrain.repeat);
+        assert_eq!(terrain.tiles.len(), 20);
+        assert!(terrain.tiles.iter().all(|row| row.len() == 20));
+        assert_eq!(terrain.tiles[0][..4], [1, 0, 0, 0]);
+        assert_eq!(terrain.tiles[19][7..13], [2, 3, 3, 3, 2, 1]);
+        assert_eq!(
+            terrain.slicing,
+            Some(SpriteDescription {
+                number_of_frames: 4,
+                border_left: 0,
+                border_up: 0,
+                frame_dimensions: (32, 32),
+            })
+        );
+
+        let clouds = &background.planes[1];
+        assert_eq!(clouds.sprite, "clouds_placeholder");
+        assert_eq!(clouds.parallax, 1.5);
+        assert!(clouds.repeat);
+        assert_eq!(clouds.tiles[0], vec![-1; 20]);
+        assert_eq!(clouds.tiles[3][..8], [-1, -1, 1, 0, 1, 0, 1, -1]);
+        assert_eq!(clouds.slicing.unwrap().number_of_frames, 2);
+
+        // Sorted by distance, objects are 640 pixels above the bottom of the map at y = 0
+        let top = 640.0 - GAME_HEIGHT as f32;
+        let distances: Vec<f32> = level.events.iter().map(distance).collect();
+        assert_eq!(
+            distances,
+            [top - 96.0, top - 96.0, top - 32.0, top, top + 160.0]
+        );
+        let enemies: Vec<&str> = level.events[..3]
+            .iter()
+            .map(|entry| match &entry.event {
+                LevelEvent::Spawn { enemy, .. } => enemy.as_str(),
+                event => panic!("expected a spawn, got {:?}", event),
+            })
+            .collect();
+        assert_eq!(enemies, ["drone", "drone", "gunner"]);
+        assert!(matches!(
+            level.events[3].event,
+            LevelEvent::BossWarning { duration } if duration == 3.0
+        ));
+        assert!(matches!(level.events[4].event, LevelEvent::EndStage));
+    }
+
+    #[test]
+    fn demo_map_formats_give_the_same_level() {
+        let tmx = load_tmx("data/maps/tiled_demo.tmx").unwrap();
+        let json = load_json("data/maps/tiled_demo.tmj").unwrap();
+
+        check_demo(&tmx);
+        check_demo(&json);
+        assert_eq!(format!("{:?}", tmx), format!("{:?}", json));
+    }
+
+    #[test]
+    fn test_map_is_valid() {
+        let level = parse_tmx(&test_map(
+            TILESET,
+            r#"encoding="csv">1,2"#,
+            r#"<object id="1" type="EndStage" x="0" y="0"/>"#,
+        ))
+        .unwrap();
+        assert_eq!(level.name, "test");
+        assert_eq!(level.background.unwrap().planes[0].tiles, [[0, 1]]);
+    }
+
+    #[test]
+    fn rejects_encoded_tile_data() {
+        let message = error_message(&test_map(TILESET, r#"encoding="base64">AQAAAAIAAAA="#, ""));
+        assert!(
+            message.contains("only CSV encoded tile data is supported"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn rejects_flipped_tiles() {
+        let flipped = format!("{}", 0x8000_0001u32);
+        let message = error_message(&test_map(
+            TILESET,
+            &format!(r#"encoding="csv">1,{}"#, flipped),
+            "",
+        ));
+        assert!(
+            message.contains("flipped or rotated tiles are not supported"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn rejects_layers_mixing_tilesets() {
+        let tilesets = format!(
+            r#"{}<tileset firstgid="3" name="more" tilewidth="32" tileheight="32" tilecount="1" columns="1"/>"#,
+            TILESET
+        );
+        let message = error_message(&test_map(&tilesets, r#"encoding="csv">1,3"#, ""));
+        assert!(
+            message.contains("all tiles of a layer must use the same tileset"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_object_types() {
+        let message = error_message(&test_map(
+            TILESET,
+            r#"encoding="csv">1,2"#,
+            r#"<object id="7" type="Teleport" x="0" y="0"/>"#,
+        ));
+        assert!(message.contains("invalid object 7"), "{}", message);
+        assert!(
+            message.contains("unknown event type \"Teleport\""),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn parses_colors() {
+        assert_eq!(parse_color("#225028").unwrap(), (0x22, 0x50, 0x28));
+        assert_eq!(parse_color("#ff225028").unwrap(), (0x22, 0x50, 0x28));
+        assert!(parse_color("#22502").is_err());
+        assert!(parse_color("#+22502").is_err());
+        assert!(parse_color("#é225028").is_err());
+    }
+}
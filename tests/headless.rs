@@ -0,0 +1,50 @@
+//! Long headless runs of the first stage
+
+use deimosreborn::{
+    component::{player_physics::PlayerPhysicsComponent, position::PositionComponent},
+    headless::{InputScript, Simulation},
+};
+use specs::{Join, WorldExt};
+
+/// Weaving across the screen while shooting, then holding right and fire until the end
+const SCRIPT: &str = "
+0 shoot
+60 left shoot
+150 left up shoot
+240 right shoot
+420 right down shoot
+510 shoot
+600 left
+700 right shoot
+";
+
+/// A bit more than 1 min at 60 Hz
+const TICKS: u64 = 4000;
+
+/// Entities alive and position of the player after running the script
+fn run(script: &InputScript) -> (usize, (f32, f32)) {
+    let mut simulation = Simulation::new().unwrap();
+    simulation.run(TICKS, script).unwrap();
+    assert_eq!(simulation.tick(), TICKS);
+
+    let world = simulation.world();
+    let positions = world.read_storage::<PositionComponent>();
+    let players = world.read_storage::<PlayerPhysicsComponent>();
+    let position = (&positions, &players)
+        .join()
+        .next()
+        .map(|(position, _)| (position.x(), position.y()))
+        .expect("player should be alive");
+    let entities = world.entities().join().count();
+
+    (entities, position)
+}
+
+#[test]
+fn runs_thousands_of_ticks_deterministically() {
+    let script = InputScript::parse(SCRIPT).unwrap();
+
+    let first = run(&script);
+    assert!(first.0 > 1, "enemies should have spawned");
+    assert_eq!(run(&script), first);
+}
@@ -0,0 +1,94 @@
+//! Level validation as done by `--validate-level`
+
+use std::{env, fs, path::PathBuf};
+
+use deimosreborn::game::validate_level;
+
+/// Level file in the temporary directory, removed when dropped
+struct LevelFile(PathBuf);
+
+impl LevelFile {
+    fn new(name: &str, content: &str) -> Self {
+        let path = env::temp_dir().join(format!("deimosreborn-{}-{}", std::process::id(), name));
+        fs::write(&path, content).unwrap();
+        Self(path)
+    }
+
+    fn errors(&self) -> String {
+        let error = validate_level(&self.0).expect_err("level should be invalid");
+        format!("{:#}", error)
+    }
+}
+
+impl Drop for LevelFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+#[test]
+fn reports_unknown_names_with_their_line() {
+    let level = LevelFile::new(
+        "unknown-names.ron",
+        r#"(
+    name: "Unknown [names]",
+    events: [
+        (trigger: Time(0.0), event: Spawn(enemy: "drone", position: (0.0, 0.0))),
+        (trigger: Time(1.0), event: Spawn(enemy: "boss (\"final\")", position: (0.0, 0.0))),
+        (
+            trigger: Time(2.0),
+            event: Spawn(enemy: "gunner", position: (0.0, 0.0), path: Some("no_such_path")),
+        ),
+        (trigger: Time(3.0), event: EndStage),
+    ],
+)
+"#,
+    );
+
+    let errors = level.errors();
+    let file = level.0.display();
+    assert!(
+        errors.contains(&format!("{}:5: event 1 (Time(1.0)): unknown enemy", file)),
+        "{}",
+        errors
+    );
+    assert!(
+        errors.contains(&format!(
+            "{}:6: event 2 (Time(2.0)): unknown path \"no_such_path\"",
+            file
+        )),
+        "{}",
+        errors
+    );
+}
+
+#[test]
+fn reports_missing_files() {
+    let level = LevelFile::new(
+        "missing-music.ron",
+        r#"(
+    name: "Missing music",
+    events: [
+        (trigger: Time(0.0), event: Music(file: "no/such/music.ogg")),
+        (trigger: Time(1.0), event: EndStage),
+    ],
+)
+"#,
+    );
+
+    let errors = level.errors();
+    assert!(
+        errors
+            .lines()
+            .any(|line| line == "missing file no/such/music.ogg"),
+        "{}",
+        errors
+    );
+}
+
+#[test]
+fn reports_syntax_errors() {
+    let level = LevelFile::new("syntax.ron", "(name: \"Broken\", events: [");
+
+    assert!(level.errors().contains("syntax.ron"));
+}
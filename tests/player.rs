@@ -0,0 +1,148 @@
+//! Player behaviour over many ticks of a headless game
+
+use std::time::Duration;
+
+use deimosreborn::{
+    component::{
+        animation_state::AnimationStateComponent, player_weapon::PlayerWeaponComponent,
+        sprite::SpriteComponent,
+    },
+    headless::{InputScript, InputSource, Simulation},
+    resource::{player_input::PlayerInput, timing::Timing},
+    GameBuilder,
+};
+use specs::{Join, WorldExt};
+
+fn simulation() -> Simulation {
+    Simulation::from_game(GameBuilder::new().without_level().build_headless().unwrap())
+}
+
+/// Ticks the player's weapon fired on within `ticks` ticks
+fn shots(simulation: &mut Simulation, ticks: u64, input: PlayerInput) -> Vec<u64> {
+    (0..ticks)
+        .filter(|_| {
+            simulation.step(input).unwrap();
+            let weapons = simulation.world().read_storage::<PlayerWeaponComponent>();
+            let weapon = weapons.join().next().expect("player should have a weapon");
+            weapon.fired
+        })
+        .collect()
+}
+
+const FIRE: PlayerInput = PlayerInput {
+    left: false,
+    right: false,
+    up: false,
+    down: false,
+    shoot_air: true,
+};
+
+#[test]
+fn weapon_fires_every_11_ticks() {
+    let mut simulation = simulation();
+
+    let fired = shots(&mut simulation, 600, FIRE);
+    assert_eq!(fired.len(), 55);
+    assert!(fired
+        .iter()
+        .enumerate()
+        .all(|(i, &tick)| tick == 11 * i as u64));
+
+    // Releasing the trigger doesn't save up shots
+    assert!(shots(&mut simulation, 100, PlayerInput::default()).is_empty());
+    assert_eq!(shots(&mut simulation, 23, FIRE), [0, 11, 22]);
+}
+
+#[test]
+fn fire_rate_does_not_depend_on_the_tick_rate() {
+    let mut simulation = simulation();
+    {
+        let mut timing = simulation.world().write_resource::<Timing>();
+        timing.delta_time = Duration::from_nanos(1_000_000_000 / 120);
+    }
+
+    let fired = shots(&mut simulation, 1200, FIRE);
+    assert_eq!(fired.len(), 55);
+    assert!(fired
+        .iter()
+        .enumerate()
+        .all(|(i, &tick)| tick == 22 * i as u64));
+}
+
+/// Input of the animation test, holding left, right and fire
+const ANIMATION_SCRIPT: &str = "
+0 left
+30
+60 right shoot
+100 shoot
+130 left shoot
+150 up
+180 right
+200
+";
+
+/// (value, ticks) runs of the player frame, as shown by the removed PlayerAnimationSystem
+#[rustfmt::skip]
+const OLD_PLAYER_FRAMES: [(usize, usize); 24] = [
+    // Banking left and back
+    (1, 2), (2, 2), (3, 26), (2, 2), (1, 2), (0, 26),
+    // Banking right and back
+    (4, 2), (5, 2), (6, 36), (5, 2), (4, 2), (0, 26),
+    (1, 2), (2, 2), (3, 16), (2, 2), (1, 2), (0, 26),
+    (4, 2), (5, 2), (6, 16), (5, 2), (4, 2), (0, 36),
+];
+
+/// Flare of a single shot, cut short by the next shot after 11 ticks
+const SHOT: [(f32, usize); 4] = [(1.0, 3), (1.1, 3), (1.2, 3), (1.1, 2)];
+
+/// (value, ticks) runs of the glow scale, as shown by the removed PlayerAnimationSystem
+fn old_glow_scales() -> Vec<(f32, usize)> {
+    let mut scales = vec![(0.5, 60)];
+    for _ in 0..8 {
+        scales.extend(SHOT);
+    }
+    scales.extend([
+        (1.0, 3),
+        (1.1, 3),
+        (1.2, 3),
+        (1.1, 3),
+        (1.0, 3),
+        (0.9, 3),
+        (0.5, 74),
+    ]);
+    scales
+}
+
+fn expand<T: Copy>(runs: &[(T, usize)]) -> Vec<T> {
+    runs.iter()
+        .flat_map(|&(value, ticks)| std::iter::repeat_n(value, ticks))
+        .collect()
+}
+
+#[test]
+fn animations_match_the_old_player_animation() {
+    let script = InputScript::parse(ANIMATION_SCRIPT).unwrap();
+    let mut simulation = simulation();
+
+    let mut frames = Vec::new();
+    let mut scales = Vec::new();
+    for tick in 0..240 {
+        simulation.step(script.input_at(tick)).unwrap();
+
+        let world = simulation.world();
+        let sprites = world.read_storage::<SpriteComponent>();
+        let weapons = world.read_storage::<PlayerWeaponComponent>();
+        let states = world.read_storage::<AnimationStateComponent>();
+        let (player, _) = (&world.entities(), &weapons).join().next().unwrap();
+        let (glow, _) = (&world.entities(), &states)
+            .join()
+            .find(|(_, state)| state.parameter_source == Some(player))
+            .expect("player should have a glow");
+
+        frames.push(sprites.get(player).unwrap().current_frame_idx);
+        scales.push(sprites.get(glow).unwrap().scale_factor);
+    }
+
+    assert_eq!(frames, expand(&OLD_PLAYER_FRAMES));
+    assert_eq!(scales, expand(&old_glow_scales()));
+}
@@ -0,0 +1,72 @@
+//! Regression tests from recorded play sessions
+
+use deimosreborn::{
+    component::{player_physics::PlayerPhysicsComponent, position::PositionComponent},
+    headless::{InputScript, InputSource, Simulation},
+    replay::Replay,
+    resource::game_state::GameState,
+};
+use specs::{Join, WorldExt};
+
+/// First stage played for 30 s, moving around while shooting (recorded from stage1.txt)
+const STAGE1_REPLAY: &str = "tests/replays/stage1.drrp";
+
+#[derive(Debug, PartialEq)]
+struct Outcome {
+    score: u64,
+    lives: u32,
+    player_position: (f32, f32),
+    entities: usize,
+}
+
+fn play<I: InputSource>(ticks: u64, input: &I) -> Outcome {
+    let mut simulation = Simulation::new().unwrap();
+    simulation.run(ticks, input).unwrap();
+
+    let world = simulation.world();
+    let game_state = world.read_resource::<GameState>();
+    let positions = world.read_storage::<PositionComponent>();
+    let players = world.read_storage::<PlayerPhysicsComponent>();
+    let (position, _) = (&positions, &players).join().next().unwrap();
+
+    let outcome = Outcome {
+        score: game_state.score,
+        lives: game_state.lives,
+        player_position: (position.x(), position.y()),
+        entities: world.entities().join().count(),
+    };
+    outcome
+}
+
+#[test]
+fn stage1_replay_ends_the_same() {
+    let replay = Replay::load(STAGE1_REPLAY).unwrap();
+    assert_eq!(replay.len(), 1800);
+
+    assert_eq!(
+        play(replay.len() as u64, &replay),
+        Outcome {
+            score: 950,
+            lives: 3,
+            player_position: (564.01, 433.5),
+            entities: 40,
+        }
+    );
+}
+
+#[test]
+fn recorded_session_plays_back_the_same() {
+    let script =
+        InputScript::parse("0 shoot\n50 left shoot\n200 up right\n400 down shoot\n").unwrap();
+    let mut recording = Replay::new();
+    for tick in 0..600 {
+        recording.record(script.input_at(tick));
+    }
+
+    let file = std::env::temp_dir().join(format!("deimosreborn-{}.drrp", std::process::id()));
+    recording.save(&file).unwrap();
+    let replay = Replay::load(&file);
+    std::fs::remove_file(&file).unwrap();
+
+    assert_eq!(play(600, &replay.unwrap()), play(600, &script));
+}